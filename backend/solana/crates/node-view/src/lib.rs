@@ -0,0 +1,120 @@
+//! Read-only access to `thepublic_node_registry` accounts for sibling programs.
+//!
+//! `NodeView::load` accepts a node account only if the registry program owns
+//! it, it deserializes as the current `NodeAccount` layout and it sits at the
+//! canonical `[b"node", node_id]` PDA, so a forged or stale copy cannot stand
+//! in for a registered node. Programs typically take the node and the
+//! registry's network state as `UncheckedAccount`s and check them in a
+//! constraint:
+//!
+//! ```ignore
+//! /// CHECK: Validated by `NodeView::load`
+//! #[account(
+//!     constraint = NodeView::load(&node_account)?
+//!         .require_eligible(&NodeView::load_network_state(&network_state)?)
+//!         .is_ok() @ MyError::NodeNotEligible
+//! )]
+//! pub node_account: UncheckedAccount<'info>,
+//! ```
+
+use anchor_lang::prelude::*;
+pub use thepublic_node_registry::state::{NetworkState, NodeAccount, NodeStatus, PerformanceHistory};
+pub use thepublic_node_registry::ID as NODE_REGISTRY_PROGRAM_ID;
+
+#[error_code(offset = 7000)]
+pub enum NodeViewError {
+    #[msg("Account is not owned by the node registry")]
+    InvalidOwner,
+    #[msg("Account is not at the expected registry PDA")]
+    InvalidAddress,
+    #[msg("Node is not active")]
+    NodeNotActive,
+    #[msg("Node reputation is below the network minimum")]
+    ReputationTooLow,
+}
+
+/// Registry PDA of the node with `node_id`
+pub fn node_address(node_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"node", node_id.as_ref()], &NODE_REGISTRY_PROGRAM_ID)
+}
+
+/// Registry PDA of the network state
+pub fn network_state_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"network_state"], &NODE_REGISTRY_PROGRAM_ID)
+}
+
+/// Registry PDA of the performance history of the node at `node`
+pub fn performance_history_address(node: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"performance_history", node.as_ref()],
+        &NODE_REGISTRY_PROGRAM_ID,
+    )
+}
+
+/// A validated snapshot of a registry `NodeAccount`
+pub struct NodeView {
+    /// Address the node account was loaded from
+    pub address: Pubkey,
+    pub node: NodeAccount,
+}
+
+impl NodeView {
+    /// Loads a node account, checking owner, layout and PDA
+    pub fn load(info: &AccountInfo) -> Result<Self> {
+        let node: NodeAccount = deserialize_registry_account(info)?;
+
+        let (expected, _) = node_address(&node.node_id);
+        require_keys_eq!(info.key(), expected, NodeViewError::InvalidAddress);
+
+        Ok(Self {
+            address: info.key(),
+            node,
+        })
+    }
+
+    /// Loads the registry's network state, checking owner, layout and PDA
+    pub fn load_network_state(info: &AccountInfo) -> Result<NetworkState> {
+        let network_state: NetworkState = deserialize_registry_account(info)?;
+
+        let (expected, _) = network_state_address();
+        require_keys_eq!(info.key(), expected, NodeViewError::InvalidAddress);
+
+        Ok(network_state)
+    }
+
+    /// Loads this node's heartbeat history, checking owner, layout and PDA
+    pub fn load_performance_history(&self, info: &AccountInfo) -> Result<PerformanceHistory> {
+        let history: PerformanceHistory = deserialize_registry_account(info)?;
+
+        let (expected, _) = performance_history_address(&self.address);
+        require_keys_eq!(info.key(), expected, NodeViewError::InvalidAddress);
+
+        Ok(history)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.node.status == NodeStatus::Active
+    }
+
+    /// Whether `key` is the node's owner or operator hotkey
+    pub fn is_operator(&self, key: &Pubkey) -> bool {
+        self.node.is_operator(key)
+    }
+
+    /// Requires the node to be active and at or above `min_reputation`
+    pub fn require_eligible(&self, network_state: &NetworkState) -> Result<()> {
+        require!(self.is_active(), NodeViewError::NodeNotActive);
+        require!(
+            self.node.reputation_score >= network_state.min_reputation,
+            NodeViewError::ReputationTooLow
+        );
+        Ok(())
+    }
+}
+
+fn deserialize_registry_account<T: AccountDeserialize>(info: &AccountInfo) -> Result<T> {
+    require_keys_eq!(*info.owner, NODE_REGISTRY_PROGRAM_ID, NodeViewError::InvalidOwner);
+
+    let data = info.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..])
+}
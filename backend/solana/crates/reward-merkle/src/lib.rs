@@ -0,0 +1,174 @@
+//! Merkle trees over per-period reward allocations.
+//!
+//! The rewards program verifies claims with `leaf_hash` and `verify_proof`
+//! from this crate, and the off-chain distributor builds the tree and proofs
+//! with `RewardTree`, so both sides share one leaf format:
+//!
+//! - leaf: `keccak(0x00 || period_le || index_le || node || amount_le)`
+//! - inner node: `keccak(0x01 || left || right)`
+//!
+//! Children are ordered by position, and the last node of an odd-length level
+//! is paired with itself.
+
+use solana_program::keccak;
+use solana_program::pubkey::Pubkey;
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// One node's allocation in a distribution
+#[derive(Clone, Debug, PartialEq)]
+pub struct RewardLeaf {
+    /// Registry `NodeAccount` the reward is credited to
+    pub node: Pubkey,
+    pub amount: u64,
+}
+
+pub fn leaf_hash(period: u64, index: u32, node: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[
+        LEAF_PREFIX,
+        &period.to_le_bytes(),
+        &index.to_le_bytes(),
+        node.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    keccak::hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+/// Whether `proof` links the leaf at `index` to `root`
+pub fn verify_proof(root: &[u8; 32], leaf: [u8; 32], index: u32, proof: &[[u8; 32]]) -> bool {
+    let mut hash = leaf;
+    let mut position = index;
+
+    for sibling in proof {
+        hash = if position & 1 == 0 {
+            node_hash(&hash, sibling)
+        } else {
+            node_hash(sibling, &hash)
+        };
+        position /= 2;
+    }
+
+    // Leftover position bits mean the proof is too short for the index
+    position == 0 && hash == *root
+}
+
+/// A distribution's full tree, leaves first
+pub struct RewardTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl RewardTree {
+    /// Builds the tree for `period`; leaf indexes follow `leaves` order
+    pub fn new(period: u64, leaves: &[RewardLeaf]) -> Self {
+        let mut level: Vec<[u8; 32]> = leaves
+            .iter()
+            .enumerate()
+            .map(|(index, leaf)| leaf_hash(period, index as u32, &leaf.node, leaf.amount))
+            .collect();
+        let mut levels = Vec::new();
+
+        while level.len() > 1 {
+            let next = level
+                .chunks(2)
+                .map(|pair| node_hash(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+                .collect();
+            levels.push(level);
+            level = next;
+        }
+        levels.push(level);
+
+        Self { levels }
+    }
+
+    /// Root to publish with `distribute_rewards`, zero for an empty tree
+    pub fn root(&self) -> [u8; 32] {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or([0; 32])
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sibling hashes from the leaf at `index` up to the root
+    pub fn proof(&self, index: u32) -> Option<Vec<[u8; 32]>> {
+        let mut position = index as usize;
+        if position >= self.len() {
+            return None;
+        }
+
+        let mut proof = Vec::with_capacity(self.levels.len() - 1);
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = position ^ 1;
+            proof.push(*level.get(sibling).unwrap_or(&level[position]));
+            position /= 2;
+        }
+
+        Some(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: u64) -> Vec<RewardLeaf> {
+        (0..count)
+            .map(|amount| RewardLeaf {
+                node: Pubkey::new_unique(),
+                amount: amount * 1_000,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn proofs_verify_for_every_leaf() {
+        for count in 1..=9 {
+            let leaves = leaves(count);
+            let tree = RewardTree::new(7, &leaves);
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let index = index as u32;
+                let proof = tree.proof(index).unwrap();
+                let hash = leaf_hash(7, index, &leaf.node, leaf.amount);
+                assert!(verify_proof(&tree.root(), hash, index, &proof));
+            }
+            assert!(tree.proof(count as u32).is_none());
+        }
+    }
+
+    #[test]
+    fn proofs_reject_altered_claims() {
+        let leaves = leaves(5);
+        let tree = RewardTree::new(3, &leaves);
+        let proof = tree.proof(2).unwrap();
+        let leaf = &leaves[2];
+
+        // Wrong amount, period, index or node
+        let altered = [
+            (leaf_hash(3, 2, &leaf.node, leaf.amount + 1), 2),
+            (leaf_hash(4, 2, &leaf.node, leaf.amount), 2),
+            (leaf_hash(3, 3, &leaf.node, leaf.amount), 3),
+            (leaf_hash(3, 2, &leaves[1].node, leaf.amount), 2),
+        ];
+        for (hash, index) in altered {
+            assert!(!verify_proof(&tree.root(), hash, index, &proof));
+        }
+
+        // A truncated proof leaves index bits unconsumed
+        let hash = leaf_hash(3, 2, &leaf.node, leaf.amount);
+        assert!(!verify_proof(&tree.root(), hash, 2, &proof[..1]));
+    }
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use crate::errors::*;
+
+/// Offsets header of the Ed25519 precompile instruction data
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_SERIALIZED_SIZE: usize = 14;
+const PUBKEY_SERIALIZED_SIZE: usize = 32;
+
+/// Length of the message a vendor signs to attest a device
+pub const ATTESTATION_MESSAGE_LEN: usize = 32 + 32 + 32;
+
+/// Message a hardware vendor signs: `node_id || hardware_hash || owner`
+pub fn attestation_message(
+    node_id: &[u8; 32],
+    hardware_hash: &[u8; 32],
+    owner: &Pubkey,
+) -> [u8; ATTESTATION_MESSAGE_LEN] {
+    let mut message = [0u8; ATTESTATION_MESSAGE_LEN];
+    message[..32].copy_from_slice(node_id);
+    message[32..64].copy_from_slice(hardware_hash);
+    message[64..].copy_from_slice(owner.as_ref());
+    message
+}
+
+/// Looks for an Ed25519 verify instruction immediately before the current one
+/// and returns the signing key if it covers `expected_message`.
+///
+/// The runtime has already checked the signature by the time this program
+/// runs, so only the signed payload and key need inspecting. Returns `None`
+/// when no Ed25519 instruction precedes the current one.
+pub fn find_attestation(
+    instructions_sysvar: &AccountInfo,
+    expected_message: &[u8],
+) -> Result<Option<Pubkey>> {
+    let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+    if current_index == 0 {
+        return Ok(None);
+    }
+
+    let ix = load_instruction_at_checked(current_index - 1, instructions_sysvar)?;
+    if ix.program_id != ed25519_program::ID {
+        return Ok(None);
+    }
+
+    let data = &ix.data;
+    require!(
+        data.len() >= SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SERIALIZED_SIZE && data[0] == 1,
+        NodeRegistryError::InvalidAttestation
+    );
+
+    let read_u16 = |offset: usize| -> usize {
+        let start = SIGNATURE_OFFSETS_START + offset;
+        u16::from_le_bytes([data[start], data[start + 1]]) as usize
+    };
+
+    // Every part of the signature must live in the precompile instruction itself
+    let signature_ix_index = read_u16(2);
+    let pubkey_offset = read_u16(4);
+    let pubkey_ix_index = read_u16(6);
+    let message_offset = read_u16(8);
+    let message_size = read_u16(10);
+    let message_ix_index = read_u16(12);
+
+    let this_ix = u16::MAX as usize;
+    require!(
+        signature_ix_index == this_ix && pubkey_ix_index == this_ix && message_ix_index == this_ix,
+        NodeRegistryError::InvalidAttestation
+    );
+
+    let pubkey_bytes = data
+        .get(pubkey_offset..pubkey_offset + PUBKEY_SERIALIZED_SIZE)
+        .ok_or(NodeRegistryError::InvalidAttestation)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(NodeRegistryError::InvalidAttestation)?;
+
+    require!(message == expected_message, NodeRegistryError::InvalidAttestation);
+
+    let vendor = Pubkey::try_from(pubkey_bytes)
+        .map_err(|_| error!(NodeRegistryError::InvalidAttestation))?;
+
+    Ok(Some(vendor))
+}
@@ -18,4 +18,62 @@ pub enum NodeRegistryError {
     InvalidNodeStatus,
     #[msg("Reputation too low")]
     ReputationTooLow,
+    #[msg("Invalid network parameter")]
+    InvalidNetworkParameter,
+    #[msg("No authority transfer pending")]
+    NoPendingAuthority,
+    #[msg("Node account must be writable")]
+    NodeAccountNotWritable,
+    #[msg("Insufficient stake")]
+    InsufficientStake,
+    #[msg("Invalid stake amount")]
+    InvalidStakeAmount,
+    #[msg("No unstake pending")]
+    NoPendingUnstake,
+    #[msg("Unstake cooldown has not elapsed")]
+    UnstakeCooldownActive,
+    #[msg("Stake must be withdrawn before deregistering")]
+    StakeNotWithdrawn,
+    #[msg("Invalid hardware attestation")]
+    InvalidAttestation,
+    #[msg("Hardware vendor is not approved")]
+    UnknownHardwareVendor,
+    #[msg("Hardware vendor is already approved")]
+    VendorAlreadyApproved,
+    #[msg("Too many hardware vendors")]
+    TooManyVendors,
+    #[msg("Hardware is already attested")]
+    HardwareAlreadyVerified,
+    #[msg("No ownership transfer pending")]
+    NoPendingOwner,
+    #[msg("Relocation cooldown has not elapsed")]
+    RelocationCooldownActive,
+    #[msg("Invalid declared bandwidth")]
+    InvalidDeclaredBandwidth,
+    #[msg("Heartbeat submitted too soon after the previous one")]
+    HeartbeatTooFrequent,
+    #[msg("Data transferred counter went backwards")]
+    DataCounterRegressed,
+    #[msg("Data transferred exceeds declared bandwidth")]
+    ImplausibleDataTransferred,
+    #[msg("Users served exceeds declared bandwidth")]
+    ImplausibleUsersServed,
+    #[msg("Average response time out of range")]
+    ImplausibleResponseTime,
+    #[msg("Node account is already on the current layout")]
+    AccountAlreadyMigrated,
+    #[msg("Index page does not match the node")]
+    IndexPageMismatch,
+    #[msg("Index page is full")]
+    IndexPageFull,
+    #[msg("Index pages must be created in order")]
+    IndexPageOutOfOrder,
+    #[msg("Invalid bounty amount")]
+    InvalidBountyAmount,
+    #[msg("Declared bandwidth was changed too recently")]
+    BandwidthUpdateCooldownActive,
+    #[msg("Suspended nodes cannot unstake or deregister")]
+    NodeSuspended,
+    #[msg("Rent payer does not match the hardware claim")]
+    RentPayerMismatch,
 }
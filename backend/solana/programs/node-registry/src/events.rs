@@ -0,0 +1,241 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+
+#[event]
+pub struct NetworkInitialized {
+    pub authority: Pubkey,
+    pub stake_mint: Pubkey,
+    pub min_stake: u64,
+    pub min_reputation: u16,
+    pub reward_params: RewardParams,
+}
+
+#[event]
+pub struct MinReputationUpdated {
+    pub old_min_reputation: u16,
+    pub new_min_reputation: u16,
+}
+
+#[event]
+pub struct RecoveryHeartbeatsUpdated {
+    pub old_recovery_heartbeats: u16,
+    pub new_recovery_heartbeats: u16,
+}
+
+#[event]
+pub struct MinHeartbeatIntervalUpdated {
+    pub min_heartbeat_interval: i64,
+}
+
+#[event]
+pub struct StalePolicyUpdated {
+    pub heartbeat_timeout: i64,
+    pub stale_bounty: u64,
+}
+
+#[event]
+pub struct StakeParamsUpdated {
+    pub min_stake: u64,
+    pub unstake_cooldown: i64,
+}
+
+#[event]
+pub struct RelocationCooldownUpdated {
+    pub relocation_cooldown: i64,
+}
+
+#[event]
+pub struct HardwareVendorAdded {
+    pub vendor: Pubkey,
+}
+
+#[event]
+pub struct HardwareVendorRemoved {
+    pub vendor: Pubkey,
+}
+
+#[event]
+pub struct HardwareClaimReleased {
+    pub hardware_hash: [u8; 32],
+    pub node: Pubkey,
+}
+
+#[event]
+pub struct RewardParamsUpdated {
+    pub reward_params: RewardParams,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct NodeRegistered {
+    pub node: Pubkey,
+    pub node_id: [u8; 32],
+    pub owner: Pubkey,
+    pub location: Location,
+    pub hex_cell: u64,
+    pub hardware_hash: [u8; 32],
+    pub declared_bandwidth_mbps: u32,
+    pub staked_amount: u64,
+}
+
+#[event]
+pub struct NodeDeregistered {
+    pub node: Pubkey,
+    pub node_id: [u8; 32],
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct HeartbeatSubmitted {
+    pub node: Pubkey,
+    pub node_id: [u8; 32],
+    pub timestamp: i64,
+    /// SHA-256 of the Borsh-encoded `PerformanceMetrics`
+    pub metrics_digest: [u8; 32],
+    pub data_transferred: u64,
+    pub reputation_score: u16,
+}
+
+#[event]
+pub struct ReputationChanged {
+    pub node: Pubkey,
+    pub old_reputation: u16,
+    pub new_reputation: u16,
+}
+
+#[event]
+pub struct NodeStatusChanged {
+    pub node: Pubkey,
+    pub node_id: [u8; 32],
+    pub old_status: NodeStatus,
+    pub new_status: NodeStatus,
+    pub actor: StatusChangeActor,
+}
+
+#[event]
+pub struct StaleNodesMarked {
+    pub cranker: Pubkey,
+    pub nodes_marked: u32,
+    pub bounty_paid: u64,
+}
+
+#[event]
+pub struct StaleBountyFunded {
+    pub funder: Pubkey,
+    pub amount: u64,
+    /// Lamports available for `mark_stale` bounties after the deposit
+    pub bounty_pool: u64,
+}
+
+#[event]
+pub struct StakeAdded {
+    pub node: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub staked_amount: u64,
+}
+
+#[event]
+pub struct UnstakeRequested {
+    pub node: Pubkey,
+    pub amount: u64,
+    pub pending_unstake: u64,
+    pub available_at: i64,
+}
+
+#[event]
+pub struct StakeWithdrawn {
+    pub node: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub staked_amount: u64,
+}
+
+#[event]
+pub struct RewardsCredited {
+    pub node: Pubkey,
+    pub amount: u64,
+    pub total_rewards: u64,
+}
+
+#[event]
+pub struct NodeSlashed {
+    pub node: Pubkey,
+    pub amount: u64,
+    pub reason: SlashReason,
+    pub staked_amount: u64,
+}
+
+#[event]
+pub struct HardwareAttested {
+    pub node: Pubkey,
+    pub node_id: [u8; 32],
+    pub hardware_hash: [u8; 32],
+    pub vendor: Pubkey,
+}
+
+#[event]
+pub struct NodeOwnershipTransferProposed {
+    pub node: Pubkey,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+}
+
+#[event]
+pub struct NodeOwnershipTransferred {
+    pub node: Pubkey,
+    pub old_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct OperatorHotkeyUpdated {
+    pub node: Pubkey,
+    pub operator_hotkey: Option<Pubkey>,
+}
+
+#[event]
+pub struct NodeRelocated {
+    pub node: Pubkey,
+    pub location: Location,
+    pub old_hex_cell: u64,
+    pub new_hex_cell: u64,
+}
+
+#[event]
+pub struct DeclaredBandwidthUpdated {
+    pub node: Pubkey,
+    pub old_bandwidth_mbps: u32,
+    pub new_bandwidth_mbps: u32,
+}
+
+#[event]
+pub struct NodeAccountMigrated {
+    pub node: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct NodeIndexPageCreated {
+    pub index_page: Pubkey,
+    pub key: IndexKey,
+    pub page: u32,
+}
+
+#[event]
+pub struct NodeIndexSynced {
+    pub node: Pubkey,
+    pub index: NodeIndexEntry,
+}
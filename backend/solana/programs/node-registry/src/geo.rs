@@ -0,0 +1,128 @@
+//! Deterministic hexagonal grid over fixed-point coordinates.
+//!
+//! Cell centres form a hexagonal lattice laid over an equirectangular
+//! projection of micro-degrees, and a point belongs to the cell with the
+//! nearest centre. Everything is integer math, so clients and the program
+//! always agree on a node's cell. The grid plays the role of an H3 index
+//! but is not H3-compatible.
+
+/// Bounds of fixed-point coordinates in micro-degrees
+pub const MAX_LATITUDE_E6: i32 = 90_000_000;
+pub const MAX_LONGITUDE_E6: i32 = 180_000_000;
+
+/// Horizontal spacing between cell centres in micro-degrees (0.01 degrees)
+pub const HEX_CELL_WIDTH_E6: i64 = 10_000;
+
+/// Vertical spacing between cell rows, `HEX_CELL_WIDTH_E6 * sqrt(3) / 2`
+pub const HEX_CELL_ROW_HEIGHT_E6: i64 = 8_660;
+
+/// Whether fixed-point coordinates are within range
+pub fn is_valid_coordinate(latitude_e6: i32, longitude_e6: i32) -> bool {
+    (-MAX_LATITUDE_E6..=MAX_LATITUDE_E6).contains(&latitude_e6)
+        && (-MAX_LONGITUDE_E6..=MAX_LONGITUDE_E6).contains(&longitude_e6)
+}
+
+/// Index of the hex cell containing a point, packed as `row << 32 | column`
+pub fn hex_cell(latitude_e6: i32, longitude_e6: i32) -> u64 {
+    // Shift into a non-negative plane, with a one-cell margin on the left so
+    // odd-row offsets never produce negative columns
+    let x = longitude_e6 as i64 + MAX_LONGITUDE_E6 as i64 + HEX_CELL_WIDTH_E6;
+    let y = latitude_e6 as i64 + MAX_LATITUDE_E6 as i64;
+
+    // The nearest centre lies in one of the two surrounding rows, and within
+    // a row in one of the two surrounding columns
+    let base_row = y / HEX_CELL_ROW_HEIGHT_E6;
+    let mut best = (i64::MAX, 0i64, 0i64);
+
+    for row in base_row..=base_row + 1 {
+        let offset = if row % 2 == 1 { HEX_CELL_WIDTH_E6 / 2 } else { 0 };
+        let base_col = (x - offset) / HEX_CELL_WIDTH_E6;
+
+        for col in base_col..=base_col + 1 {
+            let dx = x - (col * HEX_CELL_WIDTH_E6 + offset);
+            let dy = y - row * HEX_CELL_ROW_HEIGHT_E6;
+            let distance = dx * dx + dy * dy;
+
+            if distance < best.0 {
+                best = (distance, row, col);
+            }
+        }
+    }
+
+    ((best.1 as u64) << 32) | (best.2 as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Latitude of the centres in `row`
+    fn row_latitude(row: i64) -> i32 {
+        (row * HEX_CELL_ROW_HEIGHT_E6 - MAX_LATITUDE_E6 as i64) as i32
+    }
+
+    /// Longitude of the centre of `col` in `row`
+    fn col_longitude(row: i64, col: i64) -> i32 {
+        let offset = if row % 2 == 1 { HEX_CELL_WIDTH_E6 / 2 } else { 0 };
+        (col * HEX_CELL_WIDTH_E6 + offset - HEX_CELL_WIDTH_E6 - MAX_LONGITUDE_E6 as i64) as i32
+    }
+
+    fn unpack(cell: u64) -> (u64, u64) {
+        (cell >> 32, cell & u32::MAX as u64)
+    }
+
+    #[test]
+    fn poles_map_to_first_and_last_rows() {
+        let last_row = 2 * MAX_LATITUDE_E6 as u64 / HEX_CELL_ROW_HEIGHT_E6 as u64;
+
+        assert_eq!(unpack(hex_cell(-MAX_LATITUDE_E6, 0)), (0, 18_001));
+        assert_eq!(unpack(hex_cell(MAX_LATITUDE_E6, 0)), (last_row, 18_000));
+        // Every longitude at a pole stays within the pole's row
+        for longitude_e6 in [-MAX_LONGITUDE_E6, -1, 0, 1, MAX_LONGITUDE_E6] {
+            assert_eq!(unpack(hex_cell(-MAX_LATITUDE_E6, longitude_e6)).0, 0);
+            assert_eq!(unpack(hex_cell(MAX_LATITUDE_E6, longitude_e6)).0, last_row);
+        }
+    }
+
+    #[test]
+    fn antimeridian_does_not_wrap() {
+        let east = unpack(hex_cell(0, MAX_LONGITUDE_E6));
+        let west = unpack(hex_cell(0, -MAX_LONGITUDE_E6));
+
+        // Both sides of the antimeridian share a row but not a cell
+        assert_eq!(east.0, west.0);
+        assert_eq!((west.1, east.1), (1, 36_001));
+
+        // The margin keeps odd rows, shifted half a cell east, from going negative
+        assert_eq!(unpack(hex_cell(row_latitude(1), -MAX_LONGITUDE_E6)), (1, 0));
+        assert_eq!(unpack(hex_cell(row_latitude(1), MAX_LONGITUDE_E6)), (1, 36_000));
+    }
+
+    #[test]
+    fn odd_rows_are_offset_by_half_a_cell() {
+        for (row, col) in [(1, 3), (2, 3), (101, 18_000), (102, 18_000)] {
+            let latitude_e6 = row_latitude(row);
+            let longitude_e6 = col_longitude(row, col);
+            let expected = ((row as u64) << 32) | col as u64;
+
+            assert_eq!(hex_cell(latitude_e6, longitude_e6), expected);
+            // Points closer to this centre than to any neighbour stay in the cell
+            for (dy, dx) in [(-2_000, 0), (2_000, 0), (0, -4_000), (0, 4_000)] {
+                assert_eq!(hex_cell(latitude_e6 + dy, longitude_e6 + dx), expected);
+            }
+        }
+
+        // An odd-row centre lies half a cell east of the even-row centre below it
+        let even = col_longitude(0, 3);
+        let odd = col_longitude(1, 3);
+        assert_eq!((odd - even) as i64, HEX_CELL_WIDTH_E6 / 2);
+    }
+
+    #[test]
+    fn rejects_out_of_range_coordinates() {
+        assert!(is_valid_coordinate(MAX_LATITUDE_E6, MAX_LONGITUDE_E6));
+        assert!(is_valid_coordinate(-MAX_LATITUDE_E6, -MAX_LONGITUDE_E6));
+        assert!(!is_valid_coordinate(MAX_LATITUDE_E6 + 1, 0));
+        assert!(!is_valid_coordinate(0, -MAX_LONGITUDE_E6 - 1));
+    }
+}
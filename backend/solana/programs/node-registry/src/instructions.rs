@@ -1,9 +1,123 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{hash, sysvar};
+use anchor_lang::system_program;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+use crate::attestation::*;
+use crate::migration::*;
 use crate::state::*;
 use crate::errors::*;
+use crate::events::*;
 
 #[derive(Accounts)]
-#[instruction(node_id: [u8; 32])]
+pub struct InitializeNetwork<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = NetworkState::LEN,
+        seeds = [b"network_state"],
+        bump
+    )]
+    pub network_state: Account<'info, NetworkState>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = VendorRegistry::LEN,
+        seeds = [b"vendor_registry"],
+        bump
+    )]
+    pub vendor_registry: Account<'info, VendorRegistry>,
+    
+    pub stake_mint: Account<'info, Mint>,
+    
+    /// Only the upgrade authority may initialize, so nobody can front-run
+    /// the deployment and take `NetworkState.authority`
+    #[account(
+        mut,
+        constraint = program_data.upgrade_authority_address == Some(authority.key())
+            @ NodeRegistryError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::ThepublicNodeRegistry>,
+    
+    pub program_data: Account<'info, ProgramData>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateNetworkState<'info> {
+    #[account(
+        mut,
+        seeds = [b"network_state"],
+        bump,
+        has_one = authority @ NodeRegistryError::Unauthorized
+    )]
+    pub network_state: Account<'info, NetworkState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVendorRegistry<'info> {
+    #[account(
+        mut,
+        seeds = [b"vendor_registry"],
+        bump
+    )]
+    pub vendor_registry: Account<'info, VendorRegistry>,
+    
+    #[account(
+        seeds = [b"network_state"],
+        bump,
+        has_one = authority @ NodeRegistryError::Unauthorized
+    )]
+    pub network_state: Account<'info, NetworkState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseHardwareClaim<'info> {
+    #[account(
+        mut,
+        seeds = [b"hardware", hardware_claim.hardware_hash.as_ref()],
+        bump,
+        has_one = rent_payer,
+        close = rent_payer
+    )]
+    pub hardware_claim: Account<'info, HardwareClaim>,
+    
+    /// CHECK: Receives the claim's rent, checked against `hardware_claim.rent_payer`
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"network_state"],
+        bump,
+        has_one = authority @ NodeRegistryError::Unauthorized
+    )]
+    pub network_state: Account<'info, NetworkState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"network_state"],
+        bump
+    )]
+    pub network_state: Account<'info, NetworkState>,
+    
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(node_id: [u8; 32], location: Location, hardware_hash: [u8; 32])]
 pub struct RegisterNode<'info> {
     #[account(
         init,
@@ -24,11 +138,69 @@ pub struct RegisterNode<'info> {
     )]
     pub network_state: Account<'info, NetworkState>,
     
+    #[account(
+        init,
+        payer = owner,
+        space = PerformanceHistory::LEN,
+        seeds = [b"performance_history", node_account.key().as_ref()],
+        bump
+    )]
+    pub performance_history: Account<'info, PerformanceHistory>,
+    
+    #[account(
+        seeds = [b"vendor_registry"],
+        bump
+    )]
+    pub vendor_registry: Account<'info, VendorRegistry>,
+    
+    /// CHECK: Instructions sysvar, read for a preceding Ed25519 attestation
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    
+    #[account(address = network_state.stake_mint)]
+    pub stake_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = owner,
+        token::mint = stake_mint,
+        token::authority = node_account,
+        seeds = [b"stake_vault", node_account.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = stake_mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    /// Page of the node's country index with room for it
+    #[account(mut)]
+    pub country_index_page: Box<Account<'info, NodeIndexPage>>,
+    
+    /// Page of the `Inactive` status index with room for it
+    #[account(mut)]
+    pub status_index_page: Box<Account<'info, NodeIndexPage>>,
+    
+    /// Created here, or rejected in the handler if another node holds it
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = HardwareClaim::LEN,
+        seeds = [b"hardware", hardware_hash.as_ref()],
+        bump
+    )]
+    pub hardware_claim: Box<Account<'info, HardwareClaim>>,
+    
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateNodeStatus<'info> {
+pub struct AttestHardware<'info> {
     #[account(
         mut,
         has_one = owner,
@@ -37,7 +209,30 @@ pub struct UpdateNodeStatus<'info> {
     )]
     pub node_account: Account<'info, NodeAccount>,
     
+    #[account(
+        seeds = [b"vendor_registry"],
+        bump
+    )]
+    pub vendor_registry: Account<'info, VendorRegistry>,
+    
+    /// CHECK: Instructions sysvar, read for a preceding Ed25519 attestation
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    
     pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateNodeStatus<'info> {
+    #[account(
+        mut,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump,
+        constraint = node_account.is_operator(&operator.key()) @ NodeRegistryError::Unauthorized
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    pub operator: Signer<'info>,
     
     #[account(
         mut,
@@ -45,34 +240,62 @@ pub struct UpdateNodeStatus<'info> {
         bump
     )]
     pub network_state: Account<'info, NetworkState>,
+    
+    /// Page listing the node under its indexed status
+    #[account(mut)]
+    pub old_status_page: Box<Account<'info, NodeIndexPage>>,
+    
+    /// Page of the new status index with room for the node
+    #[account(mut)]
+    pub new_status_page: Box<Account<'info, NodeIndexPage>>,
 }
 
 #[derive(Accounts)]
-pub struct SubmitHeartbeat<'info> {
+pub struct AuthorityUpdateNodeStatus<'info> {
     #[account(
         mut,
-        has_one = owner,
         seeds = [b"node", node_account.node_id.as_ref()],
         bump
     )]
     pub node_account: Account<'info, NodeAccount>,
     
-    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"network_state"],
+        bump,
+        has_one = authority @ NodeRegistryError::Unauthorized
+    )]
+    pub network_state: Account<'info, NetworkState>,
+    
+    pub authority: Signer<'info>,
+    
+    /// Page listing the node under its indexed status
+    #[account(mut)]
+    pub old_status_page: Box<Account<'info, NodeIndexPage>>,
+    
+    /// Page of the new status index with room for the node
+    #[account(mut)]
+    pub new_status_page: Box<Account<'info, NodeIndexPage>>,
 }
 
 #[derive(Accounts)]
-pub struct DeregisterNode<'info> {
+pub struct SubmitHeartbeat<'info> {
     #[account(
         mut,
-        has_one = owner,
         seeds = [b"node", node_account.node_id.as_ref()],
         bump,
-        close = owner
+        constraint = node_account.is_operator(&operator.key()) @ NodeRegistryError::Unauthorized
     )]
     pub node_account: Account<'info, NodeAccount>,
     
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    pub operator: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"performance_history", node_account.key().as_ref()],
+        bump
+    )]
+    pub performance_history: Account<'info, PerformanceHistory>,
     
     #[account(
         mut,
@@ -82,108 +305,1489 @@ pub struct DeregisterNode<'info> {
     pub network_state: Account<'info, NetworkState>,
 }
 
-pub fn register_node(
-    ctx: Context<RegisterNode>,
-    node_id: [u8; 32],
-    location: Location,
-    hardware_hash: [u8; 32],
-) -> Result<()> {
-    let node_account = &mut ctx.accounts.node_account;
-    let network_state = &mut ctx.accounts.network_state;
-    let clock = Clock::get()?;
-
-    // Validate location
-    require!(
-        location.latitude >= -90.0 && location.latitude <= 90.0,
-        NodeRegistryError::InvalidLocation
-    );
-    require!(
-        location.longitude >= -180.0 && location.longitude <= 180.0,
-        NodeRegistryError::InvalidLocation
-    );
-
-    // Initialize node account
-    node_account.owner = ctx.accounts.owner.key();
-    node_account.node_id = node_id;
-    node_account.location = location;
-    node_account.hardware_hash = hardware_hash;
-    node_account.status = NodeStatus::Inactive;
-    node_account.registration_time = clock.unix_timestamp;
-    node_account.last_heartbeat = clock.unix_timestamp;
-    node_account.reputation_score = 500; // Start with neutral reputation
-    node_account.total_rewards = 0;
-    node_account.performance_metrics = PerformanceMetrics::default();
-
-    // Update network state
-    network_state.total_nodes = network_state.total_nodes.checked_add(1)
-        .ok_or(NodeRegistryError::MathOverflow)?;
-
-    msg!("Node registered: {:?}", node_id);
+#[derive(Accounts)]
+pub struct GetPerformanceSummary<'info> {
+    #[account(
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, NodeAccount>,
     
-    Ok(())
+    #[account(
+        seeds = [b"performance_history", node_account.key().as_ref()],
+        bump
+    )]
+    pub performance_history: Account<'info, PerformanceHistory>,
 }
 
-pub fn update_node_status(
-    ctx: Context<UpdateNodeStatus>,
-    status: NodeStatus,
-) -> Result<()> {
-    let node_account = &mut ctx.accounts.node_account;
-    let network_state = &mut ctx.accounts.network_state;
-    let old_status = node_account.status.clone();
-
-    // Update status
-    node_account.status = status.clone();
-
-    // Update network active node count
-    match (old_status, status) {
-        (NodeStatus::Active, NodeStatus::Inactive | NodeStatus::Maintenance | NodeStatus::Suspended) => {
-            network_state.active_nodes = network_state.active_nodes.saturating_sub(1);
-        },
-        (NodeStatus::Inactive | NodeStatus::Maintenance | NodeStatus::Suspended, NodeStatus::Active) => {
-            network_state.active_nodes = network_state.active_nodes.checked_add(1)
-                .ok_or(NodeRegistryError::MathOverflow)?;
-        },
-        _ => {} // No change in active count
-    }
-
-    msg!("Node status updated to: {:?}", status);
+#[derive(Accounts)]
+pub struct MarkStale<'info> {
+    #[account(
+        mut,
+        seeds = [b"network_state"],
+        bump
+    )]
+    pub network_state: Account<'info, NetworkState>,
     
-    Ok(())
+    #[account(mut)]
+    pub cranker: Signer<'info>,
 }
 
-pub fn submit_heartbeat(
-    ctx: Context<SubmitHeartbeat>,
-    performance_metrics: PerformanceMetrics,
-) -> Result<()> {
-    let node_account = &mut ctx.accounts.node_account;
-    let clock = Clock::get()?;
-
-    // Validate performance metrics
-    require!(
-        performance_metrics.uptime_percentage <= 100,
-        NodeRegistryError::InvalidMetrics
-    );
-    require!(
-        performance_metrics.reliability_score <= 100,
-        NodeRegistryError::InvalidMetrics
-    );
-
-    // Update heartbeat and metrics
-    node_account.last_heartbeat = clock.unix_timestamp;
-    node_account.performance_metrics = performance_metrics;
-
-    // Update reputation based on performance
-    let new_reputation = calculate_reputation(&node_account.performance_metrics);
-    node_account.reputation_score = new_reputation;
-
-    msg!("Heartbeat submitted for node: {:?}", node_account.node_id);
+#[derive(Accounts)]
+pub struct FundStaleBounty<'info> {
+    #[account(
+        mut,
+        seeds = [b"network_state"],
+        bump
+    )]
+    pub network_state: Account<'info, NetworkState>,
     
-    Ok(())
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
-pub fn deregister_node(ctx: Context<DeregisterNode>) -> Result<()> {
-    let node_account = &ctx.accounts.node_account;
-    let network_state = &mut ctx.accounts.network_state;
+#[derive(Accounts)]
+pub struct RelocateNode<'info> {
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    #[account(
+        seeds = [b"network_state"],
+        bump
+    )]
+    pub network_state: Account<'info, NetworkState>,
+    
+    pub owner: Signer<'info>,
+    
+    /// Page listing the node under its current country
+    #[account(mut)]
+    pub old_country_page: Box<Account<'info, NodeIndexPage>>,
+    
+    /// Page of the new country index with room for the node; the same page
+    /// as `old_country_page` when the country does not change
+    #[account(mut)]
+    pub new_country_page: Box<Account<'info, NodeIndexPage>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDeclaredBandwidth<'info> {
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferNodeOwnership<'info> {
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptNodeOwnership<'info> {
+    #[account(
+        mut,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    pub new_owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetOperatorHotkey<'info> {
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AddStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    #[account(
+        seeds = [b"network_state"],
+        bump
+    )]
+    pub network_state: Account<'info, NetworkState>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake_vault", node_account.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = network_state.stake_mint
+    )]
+    pub source_token_account: Account<'info, TokenAccount>,
+    
+    pub depositor: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RequestUnstake<'info> {
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    #[account(
+        seeds = [b"network_state"],
+        bump
+    )]
+    pub network_state: Account<'info, NetworkState>,
+    
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    #[account(
+        seeds = [b"network_state"],
+        bump
+    )]
+    pub network_state: Account<'info, NetworkState>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake_vault", node_account.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = network_state.stake_mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SlashNode<'info> {
+    #[account(
+        mut,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"network_state"],
+        bump,
+        has_one = authority @ NodeRegistryError::Unauthorized
+    )]
+    pub network_state: Account<'info, NetworkState>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake_vault", node_account.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = network_state.stake_mint
+    )]
+    pub slash_destination: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreditRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    /// The rewards program's state PDA, which only that program can sign for
+    #[account(
+        seeds = [b"rewards_state"],
+        bump,
+        seeds::program = crate::REWARDS_PROGRAM_ID
+    )]
+    pub rewards_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(key: IndexKey, page: u32)]
+pub struct CreateIndexPage<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = NodeIndexPage::LEN,
+        seeds = [b"node_index", key.seed().as_ref(), page.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub index_page: Box<Account<'info, NodeIndexPage>>,
+    
+    /// Page `page - 1` of the same key, omitted for page 0
+    pub previous_page: Option<Box<Account<'info, NodeIndexPage>>>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SyncNodeIndex<'info> {
+    #[account(
+        mut,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    /// Page of the node's country index with room for it, required when the
+    /// node is not yet indexed
+    #[account(mut)]
+    pub country_index_page: Option<Box<Account<'info, NodeIndexPage>>>,
+    
+    /// Page listing the node under its indexed status, required when the
+    /// node is already indexed
+    #[account(mut)]
+    pub old_status_page: Option<Box<Account<'info, NodeIndexPage>>>,
+    
+    /// Page of the current status index with room for the node
+    #[account(mut)]
+    pub new_status_page: Box<Account<'info, NodeIndexPage>>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateNodeAccount<'info> {
+    /// CHECK: Legacy layouts cannot deserialize as `NodeAccount`; ownership is
+    /// checked here and the discriminator in `migrate_v0`
+    #[account(mut, owner = crate::ID)]
+    pub node_account: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeregisterNode<'info> {
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [b"node", node_account.node_id.as_ref()],
+        bump,
+        close = owner
+    )]
+    pub node_account: Account<'info, NodeAccount>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"network_state"],
+        bump
+    )]
+    pub network_state: Account<'info, NetworkState>,
+    
+    #[account(
+        mut,
+        seeds = [b"performance_history", node_account.key().as_ref()],
+        bump,
+        close = owner
+    )]
+    pub performance_history: Account<'info, PerformanceHistory>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake_vault", node_account.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    /// Page listing the node under its country
+    #[account(mut)]
+    pub country_index_page: Box<Account<'info, NodeIndexPage>>,
+    
+    /// Page listing the node under its indexed status
+    #[account(mut)]
+    pub status_index_page: Box<Account<'info, NodeIndexPage>>,
+    
+    /// CHECK: The node's hardware claim PDA; closed by the handler unless the
+    /// authority released it
+    #[account(
+        mut,
+        seeds = [b"hardware", node_account.hardware_hash.as_ref()],
+        bump
+    )]
+    pub hardware_claim: UncheckedAccount<'info>,
+    
+    /// CHECK: Receives the hardware claim's rent when the handler closes it,
+    /// checked against `hardware_claim.rent_payer`
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn initialize_network(
+    ctx: Context<InitializeNetwork>,
+    min_reputation: u16,
+    min_stake: u64,
+    unstake_cooldown: i64,
+    reward_params: RewardParams,
+) -> Result<()> {
+    let network_state = &mut ctx.accounts.network_state;
+
+    require!(
+        min_reputation <= MAX_REPUTATION,
+        NodeRegistryError::InvalidNetworkParameter
+    );
+    require!(unstake_cooldown >= 0, NodeRegistryError::InvalidNetworkParameter);
+
+    network_state.authority = ctx.accounts.authority.key();
+    network_state.total_nodes = 0;
+    network_state.active_nodes = 0;
+    network_state.min_reputation = min_reputation;
+    network_state.recovery_heartbeats = DEFAULT_RECOVERY_HEARTBEATS;
+    network_state.min_heartbeat_interval = DEFAULT_MIN_HEARTBEAT_INTERVAL;
+    network_state.heartbeat_timeout = DEFAULT_HEARTBEAT_TIMEOUT;
+    network_state.stale_bounty = DEFAULT_STALE_BOUNTY;
+    network_state.stake_mint = ctx.accounts.stake_mint.key();
+    network_state.min_stake = min_stake;
+    network_state.unstake_cooldown = unstake_cooldown;
+    network_state.relocation_cooldown = DEFAULT_RELOCATION_COOLDOWN;
+    network_state.reward_params = reward_params.clone();
+    network_state.pending_authority = None;
+    network_state.registrations = 0;
+
+    ctx.accounts.vendor_registry.vendors = Vec::new();
+
+    emit!(NetworkInitialized {
+        authority: network_state.authority,
+        stake_mint: network_state.stake_mint,
+        min_stake,
+        min_reputation,
+        reward_params,
+    });
+
+    Ok(())
+}
+
+pub fn set_min_reputation(
+    ctx: Context<UpdateNetworkState>,
+    min_reputation: u16,
+) -> Result<()> {
+    let network_state = &mut ctx.accounts.network_state;
+
+    require!(
+        min_reputation <= MAX_REPUTATION,
+        NodeRegistryError::InvalidNetworkParameter
+    );
+
+    let old_min_reputation = network_state.min_reputation;
+    network_state.min_reputation = min_reputation;
+
+    emit!(MinReputationUpdated {
+        old_min_reputation,
+        new_min_reputation: min_reputation,
+    });
+
+    Ok(())
+}
+
+pub fn set_recovery_heartbeats(
+    ctx: Context<UpdateNetworkState>,
+    recovery_heartbeats: u16,
+) -> Result<()> {
+    let network_state = &mut ctx.accounts.network_state;
+
+    require!(recovery_heartbeats > 0, NodeRegistryError::InvalidNetworkParameter);
+
+    let old_recovery_heartbeats = network_state.recovery_heartbeats;
+    network_state.recovery_heartbeats = recovery_heartbeats;
+
+    emit!(RecoveryHeartbeatsUpdated {
+        old_recovery_heartbeats,
+        new_recovery_heartbeats: recovery_heartbeats,
+    });
+
+    Ok(())
+}
+
+pub fn set_min_heartbeat_interval(
+    ctx: Context<UpdateNetworkState>,
+    min_heartbeat_interval: i64,
+) -> Result<()> {
+    let network_state = &mut ctx.accounts.network_state;
+
+    require!(min_heartbeat_interval >= 0, NodeRegistryError::InvalidNetworkParameter);
+
+    network_state.min_heartbeat_interval = min_heartbeat_interval;
+
+    emit!(MinHeartbeatIntervalUpdated { min_heartbeat_interval });
+
+    Ok(())
+}
+
+pub fn set_stale_policy(
+    ctx: Context<UpdateNetworkState>,
+    heartbeat_timeout: i64,
+    stale_bounty: u64,
+) -> Result<()> {
+    let network_state = &mut ctx.accounts.network_state;
+
+    require!(heartbeat_timeout > 0, NodeRegistryError::InvalidNetworkParameter);
+
+    network_state.heartbeat_timeout = heartbeat_timeout;
+    network_state.stale_bounty = stale_bounty;
+
+    emit!(StalePolicyUpdated {
+        heartbeat_timeout,
+        stale_bounty,
+    });
+
+    Ok(())
+}
+
+pub fn set_stake_params(
+    ctx: Context<UpdateNetworkState>,
+    min_stake: u64,
+    unstake_cooldown: i64,
+) -> Result<()> {
+    let network_state = &mut ctx.accounts.network_state;
+
+    require!(unstake_cooldown >= 0, NodeRegistryError::InvalidNetworkParameter);
+
+    network_state.min_stake = min_stake;
+    network_state.unstake_cooldown = unstake_cooldown;
+
+    emit!(StakeParamsUpdated {
+        min_stake,
+        unstake_cooldown,
+    });
+
+    Ok(())
+}
+
+pub fn set_relocation_cooldown(
+    ctx: Context<UpdateNetworkState>,
+    relocation_cooldown: i64,
+) -> Result<()> {
+    let network_state = &mut ctx.accounts.network_state;
+
+    require!(relocation_cooldown >= 0, NodeRegistryError::InvalidNetworkParameter);
+
+    network_state.relocation_cooldown = relocation_cooldown;
+
+    emit!(RelocationCooldownUpdated { relocation_cooldown });
+
+    Ok(())
+}
+
+pub fn add_hardware_vendor(
+    ctx: Context<UpdateVendorRegistry>,
+    vendor: Pubkey,
+) -> Result<()> {
+    let vendor_registry = &mut ctx.accounts.vendor_registry;
+
+    require!(
+        !vendor_registry.is_approved(&vendor),
+        NodeRegistryError::VendorAlreadyApproved
+    );
+    require!(
+        vendor_registry.vendors.len() < MAX_HARDWARE_VENDORS,
+        NodeRegistryError::TooManyVendors
+    );
+
+    vendor_registry.vendors.push(vendor);
+
+    emit!(HardwareVendorAdded { vendor });
+
+    Ok(())
+}
+
+pub fn remove_hardware_vendor(
+    ctx: Context<UpdateVendorRegistry>,
+    vendor: Pubkey,
+) -> Result<()> {
+    let vendor_registry = &mut ctx.accounts.vendor_registry;
+
+    require!(
+        vendor_registry.is_approved(&vendor),
+        NodeRegistryError::UnknownHardwareVendor
+    );
+
+    // Nodes already attested by this vendor keep their flag
+    vendor_registry.vendors.retain(|approved| approved != &vendor);
+
+    emit!(HardwareVendorRemoved { vendor });
+
+    Ok(())
+}
+
+pub fn release_hardware_claim(ctx: Context<ReleaseHardwareClaim>) -> Result<()> {
+    let hardware_claim = &ctx.accounts.hardware_claim;
+
+    // The node keeps running; its hardware can now be registered again
+    emit!(HardwareClaimReleased {
+        hardware_hash: hardware_claim.hardware_hash,
+        node: hardware_claim.node,
+    });
+
+    Ok(())
+}
+
+pub fn update_reward_params(
+    ctx: Context<UpdateNetworkState>,
+    reward_params: RewardParams,
+) -> Result<()> {
+    let network_state = &mut ctx.accounts.network_state;
+
+    network_state.reward_params = reward_params.clone();
+
+    emit!(RewardParamsUpdated { reward_params });
+
+    Ok(())
+}
+
+pub fn propose_authority(
+    ctx: Context<UpdateNetworkState>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let network_state = &mut ctx.accounts.network_state;
+
+    network_state.pending_authority = Some(new_authority);
+
+    emit!(AuthorityProposed {
+        authority: network_state.authority,
+        pending_authority: new_authority,
+    });
+
+    Ok(())
+}
+
+pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let network_state = &mut ctx.accounts.network_state;
+    let new_authority = ctx.accounts.new_authority.key();
+
+    let pending_authority = network_state.pending_authority
+        .ok_or(NodeRegistryError::NoPendingAuthority)?;
+    require_keys_eq!(pending_authority, new_authority, NodeRegistryError::Unauthorized);
+
+    let old_authority = network_state.authority;
+    network_state.authority = new_authority;
+    network_state.pending_authority = None;
+
+    emit!(AuthorityTransferred {
+        old_authority,
+        new_authority,
+    });
+
+    Ok(())
+}
+
+pub fn register_node(
+    ctx: Context<RegisterNode>,
+    node_id: [u8; 32],
+    location: Location,
+    hardware_hash: [u8; 32],
+    declared_bandwidth_mbps: u32,
+    stake_amount: u64,
+) -> Result<()> {
+    let node_account = &mut ctx.accounts.node_account;
+    let network_state = &mut ctx.accounts.network_state;
+    let clock = Clock::get()?;
+
+    require!(stake_amount >= network_state.min_stake, NodeRegistryError::InsufficientStake);
+    require!(
+        NodeAccount::is_valid_declared_bandwidth(declared_bandwidth_mbps),
+        NodeRegistryError::InvalidDeclaredBandwidth
+    );
+
+    // Hardware is verified only if an approved vendor signed the device binding
+    let message = attestation_message(&node_id, &hardware_hash, &ctx.accounts.owner.key());
+    let hardware_vendor = find_attestation(&ctx.accounts.instructions_sysvar, &message)?;
+    if let Some(vendor) = hardware_vendor {
+        require!(
+            ctx.accounts.vendor_registry.is_approved(&vendor),
+            NodeRegistryError::UnknownHardwareVendor
+        );
+    }
+
+    // Validate location
+    require!(location.is_valid(), NodeRegistryError::InvalidLocation);
+
+    // One node per physical device
+    let hardware_claim = &mut ctx.accounts.hardware_claim;
+    require!(
+        hardware_claim.node == Pubkey::default(),
+        NodeRegistryError::NodeAlreadyExists
+    );
+    hardware_claim.node = node_account.key();
+    hardware_claim.hardware_hash = hardware_hash;
+    hardware_claim.rent_payer = ctx.accounts.owner.key();
+    hardware_claim.claimed_at = clock.unix_timestamp;
+
+    // Initialize node account
+    node_account.owner = ctx.accounts.owner.key();
+    node_account.pending_owner = None;
+    node_account.operator_hotkey = None;
+    node_account.node_id = node_id;
+    node_account.hex_cell = location.hex_cell();
+    node_account.location = location;
+    node_account.last_relocated_at = clock.unix_timestamp;
+    node_account.hardware_hash = hardware_hash;
+    node_account.declared_bandwidth_mbps = declared_bandwidth_mbps;
+    node_account.hardware_vendor = hardware_vendor;
+    node_account.status = NodeStatus::Inactive;
+    node_account.registration_time = clock.unix_timestamp;
+    node_account.last_heartbeat = clock.unix_timestamp;
+    node_account.reputation_score = 500; // Start with neutral reputation
+    node_account.total_rewards = 0;
+    node_account.performance_metrics = PerformanceMetrics::default();
+    node_account.recovery_streak = 0;
+    node_account.staked_amount = stake_amount;
+    node_account.pending_unstake = 0;
+    node_account.unstake_requested_at = 0;
+    node_account.version = NODE_ACCOUNT_VERSION;
+    node_account.index = NodeIndexEntry::default();
+    node_account.authority_suspended = false;
+    node_account.bandwidth_updated_at = clock.unix_timestamp;
+    node_account.registration_nonce = network_state.registrations
+        .checked_add(1)
+        .ok_or(NodeRegistryError::MathOverflow)?;
+    node_account.reserved = [0; NODE_ACCOUNT_RESERVED_LEN];
+
+    index_node(
+        node_account,
+        &mut ctx.accounts.country_index_page,
+        &mut ctx.accounts.status_index_page,
+    )?;
+
+    let performance_history = &mut ctx.accounts.performance_history;
+    performance_history.node_account = node_account.key();
+    performance_history.next_index = 0;
+    performance_history.samples = Vec::with_capacity(PERFORMANCE_HISTORY_LEN);
+
+    // Update network state
+    network_state.total_nodes = network_state.total_nodes.checked_add(1)
+        .ok_or(NodeRegistryError::MathOverflow)?;
+    network_state.registrations = node_account.registration_nonce;
+
+    // Lock the stake in the node's vault
+    if stake_amount > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner_token_account.to_account_info(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            stake_amount,
+        )?;
+    }
+
+    emit!(NodeRegistered {
+        node: node_account.key(),
+        node_id,
+        owner: node_account.owner,
+        location: node_account.location.clone(),
+        hex_cell: node_account.hex_cell,
+        hardware_hash,
+        declared_bandwidth_mbps,
+        staked_amount: stake_amount,
+    });
+
+    if let Some(vendor) = hardware_vendor {
+        emit!(HardwareAttested {
+            node: node_account.key(),
+            node_id,
+            hardware_hash,
+            vendor,
+        });
+    }
+    
+    Ok(())
+}
+
+pub fn attest_hardware(ctx: Context<AttestHardware>) -> Result<()> {
+    let node_account = &mut ctx.accounts.node_account;
+
+    require!(
+        !node_account.is_hardware_verified(),
+        NodeRegistryError::HardwareAlreadyVerified
+    );
+
+    let message = attestation_message(
+        &node_account.node_id,
+        &node_account.hardware_hash,
+        &node_account.owner,
+    );
+    let vendor = find_attestation(&ctx.accounts.instructions_sysvar, &message)?
+        .ok_or(NodeRegistryError::InvalidAttestation)?;
+    require!(
+        ctx.accounts.vendor_registry.is_approved(&vendor),
+        NodeRegistryError::UnknownHardwareVendor
+    );
+
+    node_account.hardware_vendor = Some(vendor);
+
+    emit!(HardwareAttested {
+        node: node_account.key(),
+        node_id: node_account.node_id,
+        hardware_hash: node_account.hardware_hash,
+        vendor,
+    });
+
+    Ok(())
+}
+
+pub fn update_node_status(
+    ctx: Context<UpdateNodeStatus>,
+    status: NodeStatus,
+) -> Result<()> {
+    let node_account = &mut ctx.accounts.node_account;
+    let network_state = &mut ctx.accounts.network_state;
+
+    // Hotkeys are limited to maintenance toggles by the transition table
+    let actor = if ctx.accounts.operator.key() == node_account.owner {
+        StatusChangeActor::Owner
+    } else {
+        StatusChangeActor::Hotkey
+    };
+
+    // Nodes below the network threshold cannot serve users
+    if status == NodeStatus::Active {
+        require!(
+            node_account.reputation_score >= network_state.min_reputation,
+            NodeRegistryError::ReputationTooLow
+        );
+        require!(
+            node_account.bonded_stake() >= network_state.min_stake,
+            NodeRegistryError::InsufficientStake
+        );
+    }
+
+    transition_node_status(node_account, network_state, status, actor)?;
+
+    reindex_status(
+        node_account,
+        &mut ctx.accounts.old_status_page,
+        &mut ctx.accounts.new_status_page,
+    )
+}
+
+pub fn authority_update_node_status(
+    ctx: Context<AuthorityUpdateNodeStatus>,
+    status: NodeStatus,
+) -> Result<()> {
+    transition_node_status(
+        &mut ctx.accounts.node_account,
+        &mut ctx.accounts.network_state,
+        status,
+        StatusChangeActor::Authority,
+    )?;
+
+    reindex_status(
+        &mut ctx.accounts.node_account,
+        &mut ctx.accounts.old_status_page,
+        &mut ctx.accounts.new_status_page,
+    )
+}
+
+pub fn submit_heartbeat(
+    ctx: Context<SubmitHeartbeat>,
+    performance_metrics: PerformanceMetrics,
+) -> Result<()> {
+    let node_account = &mut ctx.accounts.node_account;
+    let network_state = &mut ctx.accounts.network_state;
+    let clock = Clock::get()?;
+
+    // Validate performance metrics
+    require!(
+        performance_metrics.uptime_percentage <= 100,
+        NodeRegistryError::InvalidMetrics
+    );
+    require!(
+        performance_metrics.reliability_score <= 100,
+        NodeRegistryError::InvalidMetrics
+    );
+
+    let elapsed = clock.unix_timestamp.saturating_sub(node_account.last_heartbeat);
+    require!(
+        elapsed >= network_state.min_heartbeat_interval,
+        NodeRegistryError::HeartbeatTooFrequent
+    );
+    validate_heartbeat_plausibility(node_account, &performance_metrics, elapsed)?;
+
+    // Update heartbeat and metrics
+    let performance_history = &mut ctx.accounts.performance_history;
+    performance_history.record(HeartbeatSample::new(clock.unix_timestamp, &performance_metrics));
+
+    let metrics_digest = hash::hash(&performance_metrics.try_to_vec()?).to_bytes();
+    node_account.last_heartbeat = clock.unix_timestamp;
+    node_account.performance_metrics = performance_metrics;
+
+    // Update reputation based on recent performance
+    let old_reputation = node_account.reputation_score;
+    let new_reputation = performance_history.weighted_reputation();
+    node_account.reputation_score = new_reputation;
+
+    emit!(HeartbeatSubmitted {
+        node: node_account.key(),
+        node_id: node_account.node_id,
+        timestamp: clock.unix_timestamp,
+        metrics_digest,
+        data_transferred: node_account.performance_metrics.data_transferred,
+        reputation_score: new_reputation,
+    });
+
+    if new_reputation != old_reputation {
+        emit!(ReputationChanged {
+            node: node_account.key(),
+            old_reputation,
+            new_reputation,
+        });
+    }
+
+    // Suspend nodes that fall below the threshold, and let nodes suspended
+    // for low reputation recover after a streak of healthy heartbeats; only
+    // the authority lifts suspensions it imposed
+    if node_account.status != NodeStatus::Suspended {
+        if new_reputation < network_state.min_reputation {
+            transition_node_status(
+                node_account,
+                network_state,
+                NodeStatus::Suspended,
+                StatusChangeActor::Automatic,
+            )?;
+        }
+    } else if new_reputation >= network_state.min_reputation && !node_account.authority_suspended {
+        node_account.recovery_streak = node_account.recovery_streak.saturating_add(1);
+
+        if node_account.recovery_streak >= network_state.recovery_heartbeats {
+            // Recovered nodes come back inactive; the owner decides when to serve again
+            transition_node_status(
+                node_account,
+                network_state,
+                NodeStatus::Inactive,
+                StatusChangeActor::Automatic,
+            )?;
+        }
+    } else {
+        node_account.recovery_streak = 0;
+    }
+    
+    Ok(())
+}
+
+pub fn get_performance_summary(
+    ctx: Context<GetPerformanceSummary>,
+) -> Result<PerformanceSummary> {
+    Ok(ctx.accounts.performance_history.summary())
+}
+
+pub fn mark_stale<'info>(ctx: Context<'_, '_, 'info, 'info, MarkStale<'info>>) -> Result<()> {
+    let network_state = &mut ctx.accounts.network_state;
+    let now = Clock::get()?.unix_timestamp;
+    let mut nodes_marked: u32 = 0;
+
+    // Node accounts to check are passed in remaining_accounts
+    for node_info in ctx.remaining_accounts.iter() {
+        require!(node_info.is_writable, NodeRegistryError::NodeAccountNotWritable);
+
+        let mut node_account: Account<NodeAccount> = Account::try_from(node_info)?;
+        let elapsed = now.saturating_sub(node_account.last_heartbeat);
+
+        if node_account.status != NodeStatus::Active || elapsed <= network_state.heartbeat_timeout {
+            continue;
+        }
+
+        transition_node_status(
+            &mut node_account,
+            network_state,
+            NodeStatus::Inactive,
+            StatusChangeActor::Automatic,
+        )?;
+        node_account.exit(&crate::ID)?;
+
+        nodes_marked = nodes_marked.checked_add(1)
+            .ok_or(NodeRegistryError::MathOverflow)?;
+    }
+
+    // Pay the bounty out of lamports deposited through `fund_stale_bounty`,
+    // i.e. held above the network state's rent reserve
+    let bounty = network_state.stale_bounty
+        .checked_mul(nodes_marked as u64)
+        .ok_or(NodeRegistryError::MathOverflow)?;
+    let network_info = network_state.to_account_info();
+    let rent_reserve = Rent::get()?.minimum_balance(network_info.data_len());
+    let bounty_paid = bounty.min(network_info.lamports().saturating_sub(rent_reserve));
+
+    if bounty_paid > 0 {
+        **network_info.try_borrow_mut_lamports()? -= bounty_paid;
+        **ctx.accounts.cranker.to_account_info().try_borrow_mut_lamports()? += bounty_paid;
+    }
+
+    emit!(StaleNodesMarked {
+        cranker: ctx.accounts.cranker.key(),
+        nodes_marked,
+        bounty_paid,
+    });
+
+    Ok(())
+}
+
+pub fn relocate_node(ctx: Context<RelocateNode>, location: Location) -> Result<()> {
+    let node_account = &mut ctx.accounts.node_account;
+    let clock = Clock::get()?;
+
+    require!(location.is_valid(), NodeRegistryError::InvalidLocation);
+
+    let available_at = node_account.last_relocated_at
+        .checked_add(ctx.accounts.network_state.relocation_cooldown)
+        .ok_or(NodeRegistryError::MathOverflow)?;
+    require!(
+        clock.unix_timestamp >= available_at,
+        NodeRegistryError::RelocationCooldownActive
+    );
+
+    let old_country_code = node_account.location.country_code;
+    let old_hex_cell = node_account.hex_cell;
+    node_account.hex_cell = location.hex_cell();
+    node_account.location = location.clone();
+    node_account.last_relocated_at = clock.unix_timestamp;
+
+    reindex_country(
+        node_account,
+        old_country_code,
+        &mut ctx.accounts.old_country_page,
+        &mut ctx.accounts.new_country_page,
+    )?;
+
+    emit!(NodeRelocated {
+        node: node_account.key(),
+        location,
+        old_hex_cell,
+        new_hex_cell: node_account.hex_cell,
+    });
+
+    Ok(())
+}
+
+pub fn update_declared_bandwidth(
+    ctx: Context<UpdateDeclaredBandwidth>,
+    declared_bandwidth_mbps: u32,
+) -> Result<()> {
+    let node_account = &mut ctx.accounts.node_account;
+    let clock = Clock::get()?;
+
+    require!(
+        NodeAccount::is_valid_declared_bandwidth(declared_bandwidth_mbps),
+        NodeRegistryError::InvalidDeclaredBandwidth
+    );
+
+    // Keeps operators from raising the bound just before an inflated heartbeat
+    let available_at = node_account.bandwidth_updated_at
+        .checked_add(BANDWIDTH_UPDATE_COOLDOWN)
+        .ok_or(NodeRegistryError::MathOverflow)?;
+    require!(
+        clock.unix_timestamp >= available_at,
+        NodeRegistryError::BandwidthUpdateCooldownActive
+    );
+
+    let old_bandwidth_mbps = node_account.declared_bandwidth_mbps;
+    node_account.declared_bandwidth_mbps = declared_bandwidth_mbps;
+    node_account.bandwidth_updated_at = clock.unix_timestamp;
+
+    emit!(DeclaredBandwidthUpdated {
+        node: node_account.key(),
+        old_bandwidth_mbps,
+        new_bandwidth_mbps: declared_bandwidth_mbps,
+    });
+
+    Ok(())
+}
+
+pub fn transfer_node_ownership(
+    ctx: Context<TransferNodeOwnership>,
+    new_owner: Pubkey,
+) -> Result<()> {
+    let node_account = &mut ctx.accounts.node_account;
+
+    node_account.pending_owner = Some(new_owner);
+
+    emit!(NodeOwnershipTransferProposed {
+        node: node_account.key(),
+        owner: node_account.owner,
+        pending_owner: new_owner,
+    });
+
+    Ok(())
+}
+
+pub fn accept_node_ownership(ctx: Context<AcceptNodeOwnership>) -> Result<()> {
+    let node_account = &mut ctx.accounts.node_account;
+    let new_owner = ctx.accounts.new_owner.key();
+
+    let pending_owner = node_account.pending_owner
+        .ok_or(NodeRegistryError::NoPendingOwner)?;
+    require_keys_eq!(pending_owner, new_owner, NodeRegistryError::Unauthorized);
+
+    let old_owner = node_account.owner;
+    node_account.owner = new_owner;
+    node_account.pending_owner = None;
+    // The previous owner's delegated key must not carry over
+    node_account.operator_hotkey = None;
+
+    emit!(NodeOwnershipTransferred {
+        node: node_account.key(),
+        old_owner,
+        new_owner,
+    });
+
+    Ok(())
+}
+
+pub fn set_operator_hotkey(
+    ctx: Context<SetOperatorHotkey>,
+    operator_hotkey: Option<Pubkey>,
+) -> Result<()> {
+    let node_account = &mut ctx.accounts.node_account;
+
+    node_account.operator_hotkey = operator_hotkey;
+
+    emit!(OperatorHotkeyUpdated {
+        node: node_account.key(),
+        operator_hotkey,
+    });
+
+    Ok(())
+}
+
+pub fn add_stake(ctx: Context<AddStake>, amount: u64) -> Result<()> {
+    require!(amount > 0, NodeRegistryError::InvalidStakeAmount);
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.source_token_account.to_account_info(),
+                to: ctx.accounts.stake_vault.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let node_account = &mut ctx.accounts.node_account;
+    node_account.staked_amount = node_account.staked_amount.checked_add(amount)
+        .ok_or(NodeRegistryError::MathOverflow)?;
+
+    emit!(StakeAdded {
+        node: node_account.key(),
+        depositor: ctx.accounts.depositor.key(),
+        amount,
+        staked_amount: node_account.staked_amount,
+    });
+
+    Ok(())
+}
+
+pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+    let node_account = &mut ctx.accounts.node_account;
+    let network_state = &ctx.accounts.network_state;
+    let clock = Clock::get()?;
+
+    // Unstaking and re-registering would shed the suspension
+    require!(!node_account.is_suspended(), NodeRegistryError::NodeSuspended);
+    require!(
+        amount > 0 && amount <= node_account.bonded_stake(),
+        NodeRegistryError::InvalidStakeAmount
+    );
+
+    // Active nodes must keep the minimum stake bonded
+    let remaining = node_account.bonded_stake() - amount;
+    if node_account.status == NodeStatus::Active {
+        require!(remaining >= network_state.min_stake, NodeRegistryError::InsufficientStake);
+    }
+
+    // A new request restarts the cooldown for the whole pending amount
+    node_account.pending_unstake = node_account.pending_unstake.checked_add(amount)
+        .ok_or(NodeRegistryError::MathOverflow)?;
+    node_account.unstake_requested_at = clock.unix_timestamp;
+
+    let available_at = clock.unix_timestamp.checked_add(network_state.unstake_cooldown)
+        .ok_or(NodeRegistryError::MathOverflow)?;
+
+    emit!(UnstakeRequested {
+        node: node_account.key(),
+        amount,
+        pending_unstake: node_account.pending_unstake,
+        available_at,
+    });
+
+    Ok(())
+}
+
+pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+    let node_account = &ctx.accounts.node_account;
+    let clock = Clock::get()?;
+    let amount = node_account.pending_unstake;
+
+    require!(amount > 0, NodeRegistryError::NoPendingUnstake);
+
+    let available_at = node_account.unstake_requested_at
+        .checked_add(ctx.accounts.network_state.unstake_cooldown)
+        .ok_or(NodeRegistryError::MathOverflow)?;
+    require!(
+        clock.unix_timestamp >= available_at,
+        NodeRegistryError::UnstakeCooldownActive
+    );
+
+    let node_id = node_account.node_id;
+    let seeds = &[
+        b"node".as_ref(),
+        node_id.as_ref(),
+        &[ctx.bumps.node_account],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                to: ctx.accounts.owner_token_account.to_account_info(),
+                authority: ctx.accounts.node_account.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    let node_account = &mut ctx.accounts.node_account;
+    node_account.staked_amount = node_account.staked_amount.checked_sub(amount)
+        .ok_or(NodeRegistryError::MathOverflow)?;
+    node_account.pending_unstake = 0;
+    node_account.unstake_requested_at = 0;
+
+    emit!(StakeWithdrawn {
+        node: node_account.key(),
+        owner: ctx.accounts.owner.key(),
+        amount,
+        staked_amount: node_account.staked_amount,
+    });
+
+    Ok(())
+}
+
+pub fn slash_node(
+    ctx: Context<SlashNode>,
+    amount: u64,
+    reason: SlashReason,
+) -> Result<()> {
+    require!(
+        amount > 0 && amount <= ctx.accounts.node_account.staked_amount,
+        NodeRegistryError::InvalidStakeAmount
+    );
+
+    let node_id = ctx.accounts.node_account.node_id;
+    let seeds = &[
+        b"node".as_ref(),
+        node_id.as_ref(),
+        &[ctx.bumps.node_account],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                to: ctx.accounts.slash_destination.to_account_info(),
+                authority: ctx.accounts.node_account.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    let node_account = &mut ctx.accounts.node_account;
+    let network_state = &mut ctx.accounts.network_state;
+
+    node_account.staked_amount -= amount;
+    node_account.pending_unstake = node_account.pending_unstake.min(node_account.staked_amount);
+
+    // A node slashed below the minimum can no longer serve
+    if node_account.status == NodeStatus::Active
+        && node_account.bonded_stake() < network_state.min_stake
+    {
+        transition_node_status(
+            node_account,
+            network_state,
+            NodeStatus::Inactive,
+            StatusChangeActor::Automatic,
+        )?;
+    }
+
+    emit!(NodeSlashed {
+        node: node_account.key(),
+        amount,
+        reason,
+        staked_amount: node_account.staked_amount,
+    });
+
+    Ok(())
+}
+
+pub fn credit_rewards(ctx: Context<CreditRewards>, amount: u64) -> Result<()> {
+    let node_account = &mut ctx.accounts.node_account;
+
+    node_account.total_rewards = node_account.total_rewards.checked_add(amount)
+        .ok_or(NodeRegistryError::MathOverflow)?;
+
+    emit!(RewardsCredited {
+        node: node_account.key(),
+        amount,
+        total_rewards: node_account.total_rewards,
+    });
+
+    Ok(())
+}
+
+pub fn create_index_page(ctx: Context<CreateIndexPage>, key: IndexKey, page: u32) -> Result<()> {
+    if let IndexKey::Country(country_code) = key {
+        require!(
+            country_code.iter().all(u8::is_ascii_uppercase),
+            NodeRegistryError::InvalidLocation
+        );
+    }
+
+    // Pages are contiguous so clients can stop at the first missing one
+    if page > 0 {
+        let previous_page = ctx.accounts.previous_page.as_ref()
+            .ok_or(NodeRegistryError::IndexPageOutOfOrder)?;
+        require!(
+            previous_page.key == key && previous_page.page == page - 1,
+            NodeRegistryError::IndexPageOutOfOrder
+        );
+    }
+
+    let index_page = &mut ctx.accounts.index_page;
+    index_page.key = key;
+    index_page.page = page;
+    index_page.nodes = Vec::with_capacity(NODE_INDEX_PAGE_CAPACITY);
+
+    emit!(NodeIndexPageCreated {
+        index_page: index_page.key(),
+        key,
+        page,
+    });
+
+    Ok(())
+}
+
+pub fn sync_node_index(ctx: Context<SyncNodeIndex>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+
+    if !accounts.node_account.index.indexed {
+        // Accounts migrated from v0 start out unindexed
+        let country_index_page = accounts.country_index_page.as_mut()
+            .ok_or(NodeRegistryError::IndexPageMismatch)?;
+        index_node(
+            &mut accounts.node_account,
+            country_index_page,
+            &mut accounts.new_status_page,
+        )?;
+    } else {
+        // Automatic transitions leave the status index behind
+        let old_status_page = accounts.old_status_page.as_mut()
+            .ok_or(NodeRegistryError::IndexPageMismatch)?;
+        reindex_status(
+            &mut accounts.node_account,
+            old_status_page,
+            &mut accounts.new_status_page,
+        )?;
+    }
+
+    emit!(NodeIndexSynced {
+        node: accounts.node_account.key(),
+        index: accounts.node_account.index,
+    });
+
+    Ok(())
+}
+
+pub fn fund_stale_bounty(ctx: Context<FundStaleBounty>, amount: u64) -> Result<()> {
+    require!(amount > 0, NodeRegistryError::InvalidBountyAmount);
+
+    let network_info = ctx.accounts.network_state.to_account_info();
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.funder.to_account_info(),
+                to: network_info.clone(),
+            },
+        ),
+        amount,
+    )?;
+
+    let rent_reserve = Rent::get()?.minimum_balance(network_info.data_len());
+    emit!(StaleBountyFunded {
+        funder: ctx.accounts.funder.key(),
+        amount,
+        bounty_pool: network_info.lamports().saturating_sub(rent_reserve),
+    });
+
+    Ok(())
+}
+
+pub fn migrate_node_account(ctx: Context<MigrateNodeAccount>) -> Result<()> {
+    let node_info = ctx.accounts.node_account.to_account_info();
+    let node = migrate_v0(&node_info.try_borrow_data()?)?;
+
+    // Top up rent for the larger layout, then grow the account in place
+    let rent_exempt = Rent::get()?.minimum_balance(NodeAccount::LEN);
+    let shortfall = rent_exempt.saturating_sub(node_info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: node_info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    node_info.realloc(NodeAccount::LEN, true)?;
+
+    let mut data = node_info.try_borrow_mut_data()?;
+    node.try_serialize(&mut &mut data[..])?;
+
+    emit!(NodeAccountMigrated {
+        node: node_info.key(),
+        from_version: 0,
+        to_version: node.version,
+    });
+
+    Ok(())
+}
+
+pub fn deregister_node(ctx: Context<DeregisterNode>) -> Result<()> {
+    let node_account = &ctx.accounts.node_account;
+
+    require!(!node_account.is_suspended(), NodeRegistryError::NodeSuspended);
+    require!(node_account.staked_amount == 0, NodeRegistryError::StakeNotWithdrawn);
+
+    unindex_node(
+        node_account,
+        &mut ctx.accounts.country_index_page,
+        &mut ctx.accounts.status_index_page,
+    )?;
+
+    // Return the empty vault's rent to the owner
+    let node_id = node_account.node_id;
+    let seeds = &[
+        b"node".as_ref(),
+        node_id.as_ref(),
+        &[ctx.bumps.node_account],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.stake_vault.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.node_account.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    // Free the hardware for future registrations. A released claim is either
+    // gone or already held by another node, and is left alone.
+    let claim_info = ctx.accounts.hardware_claim.to_account_info();
+    if claim_info.owner == &crate::ID {
+        let hardware_claim = HardwareClaim::try_deserialize(&mut &claim_info.try_borrow_data()?[..])?;
+        if hardware_claim.node == node_account.key() {
+            // The rent goes back to whoever paid it, not a later owner
+            require_keys_eq!(
+                ctx.accounts.rent_payer.key(),
+                hardware_claim.rent_payer,
+                NodeRegistryError::RentPayerMismatch
+            );
+            let rent_payer_info = ctx.accounts.rent_payer.to_account_info();
+            let rent_payer_lamports = rent_payer_info.lamports()
+                .checked_add(claim_info.lamports())
+                .ok_or(NodeRegistryError::MathOverflow)?;
+            **rent_payer_info.try_borrow_mut_lamports()? = rent_payer_lamports;
+            **claim_info.try_borrow_mut_lamports()? = 0;
+            claim_info.assign(&system_program::ID);
+            claim_info.realloc(0, false)?;
+        }
+    }
+
+    let network_state = &mut ctx.accounts.network_state;
 
     // Update network state
     network_state.total_nodes = network_state.total_nodes.saturating_sub(1);
@@ -192,16 +1796,215 @@ pub fn deregister_node(ctx: Context<DeregisterNode>) -> Result<()> {
         network_state.active_nodes = network_state.active_nodes.saturating_sub(1);
     }
 
-    msg!("Node deregistered: {:?}", node_account.node_id);
+    emit!(NodeDeregistered {
+        node: node_account.key(),
+        node_id: node_account.node_id,
+        owner: node_account.owner,
+    });
     
     Ok(())
 }
 
-fn calculate_reputation(metrics: &PerformanceMetrics) -> u16 {
-    let uptime_score = (metrics.uptime_percentage as u16) * 5; // 0-500 points
-    let reliability_score = (metrics.reliability_score as u16) * 5; // 0-500 points
-    
-    // Average the scores and ensure within bounds
-    let reputation = (uptime_score + reliability_score) / 2;
-    reputation.min(1000).max(0)
+/// Checks self-reported metrics against the node's previous heartbeat and
+/// declared bandwidth
+fn validate_heartbeat_plausibility(
+    node_account: &NodeAccount,
+    metrics: &PerformanceMetrics,
+    elapsed: i64,
+) -> Result<()> {
+    let previous_data = node_account.performance_metrics.data_transferred;
+    let bandwidth = node_account.declared_bandwidth_mbps as u64;
+
+    // Data transferred is a cumulative counter
+    let data_delta = metrics.data_transferred.checked_sub(previous_data)
+        .ok_or(NodeRegistryError::DataCounterRegressed)?;
+
+    let max_data_delta = bandwidth
+        .saturating_mul(BYTES_PER_SECOND_PER_MBPS)
+        .saturating_mul(elapsed.max(0) as u64);
+    require!(
+        data_delta <= max_data_delta,
+        NodeRegistryError::ImplausibleDataTransferred
+    );
+
+    require!(
+        metrics.users_served as u64 <= bandwidth.saturating_mul(MAX_USERS_PER_MBPS),
+        NodeRegistryError::ImplausibleUsersServed
+    );
+
+    require!(
+        metrics.avg_response_time <= MAX_RESPONSE_TIME_MS,
+        NodeRegistryError::ImplausibleResponseTime
+    );
+
+    Ok(())
+}
+
+fn transition_node_status(
+    node_account: &mut Account<NodeAccount>,
+    network_state: &mut NetworkState,
+    new_status: NodeStatus,
+    actor: StatusChangeActor,
+) -> Result<()> {
+    let old_status = node_account.status;
+
+    require!(
+        old_status.can_transition_to(new_status, actor),
+        NodeRegistryError::InvalidNodeStatus
+    );
+
+    node_account.status = new_status;
+    if old_status == NodeStatus::Suspended || new_status == NodeStatus::Suspended {
+        node_account.recovery_streak = 0;
+    }
+    node_account.authority_suspended =
+        new_status == NodeStatus::Suspended && actor == StatusChangeActor::Authority;
+
+    update_active_nodes(network_state, old_status, new_status)?;
+
+    emit!(NodeStatusChanged {
+        node: node_account.key(),
+        node_id: node_account.node_id,
+        old_status,
+        new_status,
+        actor,
+    });
+
+    Ok(())
+}
+
+/// Lists an unindexed node under its country and current status
+fn index_node(
+    node_account: &mut Account<NodeAccount>,
+    country_page: &mut NodeIndexPage,
+    status_page: &mut NodeIndexPage,
+) -> Result<()> {
+    require!(
+        country_page.key == IndexKey::Country(node_account.location.country_code),
+        NodeRegistryError::IndexPageMismatch
+    );
+    require!(
+        status_page.key == IndexKey::Status(node_account.status),
+        NodeRegistryError::IndexPageMismatch
+    );
+
+    let node = node_account.key();
+    country_page.insert(node)?;
+    status_page.insert(node)?;
+
+    node_account.index = NodeIndexEntry {
+        indexed: true,
+        country_page: country_page.page,
+        status: node_account.status,
+        status_page: status_page.page,
+    };
+
+    Ok(())
+}
+
+/// Removes a node from the pages recorded in its index entry
+fn unindex_node(
+    node_account: &Account<NodeAccount>,
+    country_page: &mut NodeIndexPage,
+    status_page: &mut NodeIndexPage,
+) -> Result<()> {
+    let entry = node_account.index;
+    if !entry.indexed {
+        return Ok(());
+    }
+
+    require!(
+        country_page.key == IndexKey::Country(node_account.location.country_code)
+            && country_page.page == entry.country_page,
+        NodeRegistryError::IndexPageMismatch
+    );
+    require!(
+        status_page.key == IndexKey::Status(entry.status)
+            && status_page.page == entry.status_page,
+        NodeRegistryError::IndexPageMismatch
+    );
+
+    country_page.remove(&node_account.key())?;
+    status_page.remove(&node_account.key())
+}
+
+/// Moves an indexed node to the status index matching its current status
+fn reindex_status(
+    node_account: &mut Account<NodeAccount>,
+    old_page: &mut NodeIndexPage,
+    new_page: &mut NodeIndexPage,
+) -> Result<()> {
+    let entry = node_account.index;
+    if !entry.indexed || entry.status == node_account.status {
+        return Ok(());
+    }
+
+    require!(
+        old_page.key == IndexKey::Status(entry.status) && old_page.page == entry.status_page,
+        NodeRegistryError::IndexPageMismatch
+    );
+    require!(
+        new_page.key == IndexKey::Status(node_account.status),
+        NodeRegistryError::IndexPageMismatch
+    );
+
+    let node = node_account.key();
+    old_page.remove(&node)?;
+    new_page.insert(node)?;
+
+    node_account.index.status = node_account.status;
+    node_account.index.status_page = new_page.page;
+
+    Ok(())
+}
+
+/// Moves an indexed node to the country index matching its current location
+fn reindex_country(
+    node_account: &mut Account<NodeAccount>,
+    old_country_code: [u8; 2],
+    old_page: &mut NodeIndexPage,
+    new_page: &mut NodeIndexPage,
+) -> Result<()> {
+    let entry = node_account.index;
+    let new_country_code = node_account.location.country_code;
+    if !entry.indexed || old_country_code == new_country_code {
+        return Ok(());
+    }
+
+    require!(
+        old_page.key == IndexKey::Country(old_country_code) && old_page.page == entry.country_page,
+        NodeRegistryError::IndexPageMismatch
+    );
+    require!(
+        new_page.key == IndexKey::Country(new_country_code),
+        NodeRegistryError::IndexPageMismatch
+    );
+
+    let node = node_account.key();
+    old_page.remove(&node)?;
+    new_page.insert(node)?;
+
+    node_account.index.country_page = new_page.page;
+
+    Ok(())
+}
+
+fn update_active_nodes(
+    network_state: &mut NetworkState,
+    old_status: NodeStatus,
+    new_status: NodeStatus,
+) -> Result<()> {
+    match (old_status, new_status) {
+        (NodeStatus::Active, NodeStatus::Inactive | NodeStatus::Maintenance | NodeStatus::Suspended) => {
+            network_state.active_nodes = network_state.active_nodes.saturating_sub(1);
+        },
+        (NodeStatus::Inactive | NodeStatus::Maintenance | NodeStatus::Suspended, NodeStatus::Active) => {
+            network_state.active_nodes = network_state.active_nodes.checked_add(1)
+                .ok_or(NodeRegistryError::MathOverflow)?;
+        },
+        _ => {} // No change in active count
+    }
+
+    Ok(())
 }
+
@@ -2,24 +2,153 @@ use anchor_lang::prelude::*;
 
 declare_id!("11111111111111111111111111111112"); // Placeholder - replace with actual program ID
 
+/// Rewards program whose `rewards_state` PDA may credit `total_rewards`
+pub const REWARDS_PROGRAM_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("RewardsProgram11111111111111111111111111111");
+
+pub mod attestation;
+pub mod geo;
 pub mod instructions;
+pub mod migration;
 pub mod state;
 pub mod errors;
+pub mod events;
 
 use instructions::*;
+use state::*;
 
 #[program]
 pub mod thepublic_node_registry {
     use super::*;
 
-    /// Register a new node on the network
+    /// Create the network state account and set its authority (upgrade
+    /// authority only)
+    pub fn initialize_network(
+        ctx: Context<InitializeNetwork>,
+        min_reputation: u16,
+        min_stake: u64,
+        unstake_cooldown: i64,
+        reward_params: RewardParams,
+    ) -> Result<()> {
+        instructions::initialize_network(ctx, min_reputation, min_stake, unstake_cooldown, reward_params)
+    }
+
+    /// Update the minimum reputation required to stay active (admin only)
+    pub fn set_min_reputation(
+        ctx: Context<UpdateNetworkState>,
+        min_reputation: u16,
+    ) -> Result<()> {
+        instructions::set_min_reputation(ctx, min_reputation)
+    }
+
+    /// Update how many healthy heartbeats lift a suspension (admin only)
+    pub fn set_recovery_heartbeats(
+        ctx: Context<UpdateNetworkState>,
+        recovery_heartbeats: u16,
+    ) -> Result<()> {
+        instructions::set_recovery_heartbeats(ctx, recovery_heartbeats)
+    }
+
+    /// Update the minimum time between heartbeats (admin only)
+    pub fn set_min_heartbeat_interval(
+        ctx: Context<UpdateNetworkState>,
+        min_heartbeat_interval: i64,
+    ) -> Result<()> {
+        instructions::set_min_heartbeat_interval(ctx, min_heartbeat_interval)
+    }
+
+    /// Update the stale-heartbeat timeout and crank bounty (admin only)
+    pub fn set_stale_policy(
+        ctx: Context<UpdateNetworkState>,
+        heartbeat_timeout: i64,
+        stale_bounty: u64,
+    ) -> Result<()> {
+        instructions::set_stale_policy(ctx, heartbeat_timeout, stale_bounty)
+    }
+
+    /// Update the minimum stake and unstake cooldown (admin only)
+    pub fn set_stake_params(
+        ctx: Context<UpdateNetworkState>,
+        min_stake: u64,
+        unstake_cooldown: i64,
+    ) -> Result<()> {
+        instructions::set_stake_params(ctx, min_stake, unstake_cooldown)
+    }
+
+    /// Update the minimum time between node relocations (admin only)
+    pub fn set_relocation_cooldown(
+        ctx: Context<UpdateNetworkState>,
+        relocation_cooldown: i64,
+    ) -> Result<()> {
+        instructions::set_relocation_cooldown(ctx, relocation_cooldown)
+    }
+
+    /// Approve a hardware vendor's attestation key (admin only)
+    pub fn add_hardware_vendor(
+        ctx: Context<UpdateVendorRegistry>,
+        vendor: Pubkey,
+    ) -> Result<()> {
+        instructions::add_hardware_vendor(ctx, vendor)
+    }
+
+    /// Revoke a hardware vendor's attestation key (admin only)
+    pub fn remove_hardware_vendor(
+        ctx: Context<UpdateVendorRegistry>,
+        vendor: Pubkey,
+    ) -> Result<()> {
+        instructions::remove_hardware_vendor(ctx, vendor)
+    }
+
+    /// Release a hardware claim so the device can be registered again,
+    /// e.g. after theft or resale (admin only)
+    pub fn release_hardware_claim(ctx: Context<ReleaseHardwareClaim>) -> Result<()> {
+        instructions::release_hardware_claim(ctx)
+    }
+
+    /// Update reward parameters (admin only)
+    pub fn update_reward_params(
+        ctx: Context<UpdateNetworkState>,
+        reward_params: RewardParams,
+    ) -> Result<()> {
+        instructions::update_reward_params(ctx, reward_params)
+    }
+
+    /// Propose a new network authority (admin only)
+    pub fn propose_authority(
+        ctx: Context<UpdateNetworkState>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_authority(ctx, new_authority)
+    }
+
+    /// Accept a pending authority transfer
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::accept_authority(ctx)
+    }
+
+    /// Register a new node on the network, optionally preceded by an
+    /// Ed25519 vendor attestation instruction
     pub fn register_node(
         ctx: Context<RegisterNode>,
         node_id: [u8; 32],
         location: Location,
         hardware_hash: [u8; 32],
+        declared_bandwidth_mbps: u32,
+        stake_amount: u64,
     ) -> Result<()> {
-        instructions::register_node(ctx, node_id, location, hardware_hash)
+        instructions::register_node(
+            ctx,
+            node_id,
+            location,
+            hardware_hash,
+            declared_bandwidth_mbps,
+            stake_amount,
+        )
+    }
+
+    /// Attest an already registered node's hardware with a vendor signature
+    pub fn attest_hardware(ctx: Context<AttestHardware>) -> Result<()> {
+        instructions::attest_hardware(ctx)
     }
 
     /// Update node status
@@ -30,6 +159,14 @@ pub mod thepublic_node_registry {
         instructions::update_node_status(ctx, status)
     }
 
+    /// Suspend a node or lift its suspension (admin only)
+    pub fn authority_update_node_status(
+        ctx: Context<AuthorityUpdateNodeStatus>,
+        status: NodeStatus,
+    ) -> Result<()> {
+        instructions::authority_update_node_status(ctx, status)
+    }
+
     /// Submit node heartbeat with performance metrics
     pub fn submit_heartbeat(
         ctx: Context<SubmitHeartbeat>,
@@ -38,6 +175,106 @@ pub mod thepublic_node_registry {
         instructions::submit_heartbeat(ctx, performance_metrics)
     }
 
+    /// Aggregate a node's recent heartbeats (read-only view)
+    pub fn get_performance_summary(
+        ctx: Context<GetPerformanceSummary>,
+    ) -> Result<PerformanceSummary> {
+        instructions::get_performance_summary(ctx)
+    }
+
+    /// Mark active nodes without a recent heartbeat as inactive (permissionless)
+    pub fn mark_stale<'info>(ctx: Context<'_, '_, 'info, 'info, MarkStale<'info>>) -> Result<()> {
+        instructions::mark_stale(ctx)
+    }
+
+    /// Deposit lamports into the pool `mark_stale` bounties are paid from
+    pub fn fund_stale_bounty(ctx: Context<FundStaleBounty>, amount: u64) -> Result<()> {
+        instructions::fund_stale_bounty(ctx, amount)
+    }
+
+    /// Move a node to a new location, subject to the relocation cooldown
+    pub fn relocate_node(ctx: Context<RelocateNode>, location: Location) -> Result<()> {
+        instructions::relocate_node(ctx, location)
+    }
+
+    /// Change a node's declared bandwidth, subject to a cooldown
+    pub fn update_declared_bandwidth(
+        ctx: Context<UpdateDeclaredBandwidth>,
+        declared_bandwidth_mbps: u32,
+    ) -> Result<()> {
+        instructions::update_declared_bandwidth(ctx, declared_bandwidth_mbps)
+    }
+
+    /// Credit distributed rewards to a node (CPI from the rewards program only)
+    pub fn credit_rewards(ctx: Context<CreditRewards>, amount: u64) -> Result<()> {
+        instructions::credit_rewards(ctx, amount)
+    }
+
+    /// Create the next page of a country or status index (permissionless)
+    pub fn create_index_page(
+        ctx: Context<CreateIndexPage>,
+        key: IndexKey,
+        page: u32,
+    ) -> Result<()> {
+        instructions::create_index_page(ctx, key, page)
+    }
+
+    /// Bring a node's index entries up to date after an automatic status
+    /// change or migration (permissionless)
+    pub fn sync_node_index(ctx: Context<SyncNodeIndex>) -> Result<()> {
+        instructions::sync_node_index(ctx)
+    }
+
+    /// Propose a new owner for a node
+    pub fn transfer_node_ownership(
+        ctx: Context<TransferNodeOwnership>,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        instructions::transfer_node_ownership(ctx, new_owner)
+    }
+
+    /// Accept a pending node ownership transfer
+    pub fn accept_node_ownership(ctx: Context<AcceptNodeOwnership>) -> Result<()> {
+        instructions::accept_node_ownership(ctx)
+    }
+
+    /// Set or clear the hotkey allowed to submit heartbeats and toggle maintenance
+    pub fn set_operator_hotkey(
+        ctx: Context<SetOperatorHotkey>,
+        operator_hotkey: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::set_operator_hotkey(ctx, operator_hotkey)
+    }
+
+    /// Add stake to a node's vault
+    pub fn add_stake(ctx: Context<AddStake>, amount: u64) -> Result<()> {
+        instructions::add_stake(ctx, amount)
+    }
+
+    /// Start the cooldown for unbonding part of a node's stake
+    pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+        instructions::request_unstake(ctx, amount)
+    }
+
+    /// Withdraw unbonded stake once the cooldown has elapsed
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        instructions::withdraw_stake(ctx)
+    }
+
+    /// Slash a node's stake for failed coverage or fraud (admin only)
+    pub fn slash_node(
+        ctx: Context<SlashNode>,
+        amount: u64,
+        reason: SlashReason,
+    ) -> Result<()> {
+        instructions::slash_node(ctx, amount, reason)
+    }
+
+    /// Upgrade a node account written by an earlier layout version
+    pub fn migrate_node_account(ctx: Context<MigrateNodeAccount>) -> Result<()> {
+        instructions::migrate_node_account(ctx)
+    }
+
     /// Deregister a node from the network
     pub fn deregister_node(ctx: Context<DeregisterNode>) -> Result<()> {
         instructions::deregister_node(ctx)
@@ -0,0 +1,303 @@
+//! Upgrades of `NodeAccount` data written by earlier program versions.
+//!
+//! Version 0 accounts were written by the first deployed program. They store
+//! the location as floating-point degrees and predate ownership transfer,
+//! hotkeys, hex cells, hardware claims, staking, indexing and the `version`
+//! byte, so they are too short to deserialize as the current layout and every
+//! instruction rejects them until `migrate_node_account` reallocates and
+//! rewrites them.
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::errors::*;
+use crate::state::*;
+
+/// `Location` as laid out by v0
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LocationV0 {
+    /// Latitude in degrees (-90 to 90)
+    pub latitude: f64,
+    /// Longitude in degrees (-180 to 180)
+    pub longitude: f64,
+    /// Country code (ISO 3166-1 alpha-2)
+    pub country_code: [u8; 2],
+}
+
+impl LocationV0 {
+    pub const LEN: usize = 8 + 8 + 2; // latitude + longitude + country_code
+}
+
+impl TryFrom<LocationV0> for Location {
+    type Error = Error;
+
+    /// Rounds both coordinates to the nearest micro-degree
+    fn try_from(v0: LocationV0) -> Result<Self> {
+        // The ranges v0 checked at registration
+        require!(
+            (-90.0..=90.0).contains(&v0.latitude) && (-180.0..=180.0).contains(&v0.longitude),
+            NodeRegistryError::InvalidLocation
+        );
+
+        Ok(Self {
+            latitude_e6: (v0.latitude * 1_000_000.0).round() as i32,
+            longitude_e6: (v0.longitude * 1_000_000.0).round() as i32,
+            country_code: v0.country_code,
+        })
+    }
+}
+
+/// `NodeAccount` as laid out by v0
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct NodeAccountV0 {
+    pub owner: Pubkey,
+    pub node_id: [u8; 32],
+    pub location: LocationV0,
+    pub hardware_hash: [u8; 32],
+    pub status: NodeStatus,
+    pub registration_time: i64,
+    pub last_heartbeat: i64,
+    pub reputation_score: u16,
+    pub total_rewards: u64,
+    pub performance_metrics: PerformanceMetrics,
+}
+
+impl NodeAccountV0 {
+    /// Space allocated for v0 accounts
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        32 + // node_id
+        LocationV0::LEN +
+        32 + // hardware_hash
+        NodeStatus::LEN +
+        8 + // registration_time
+        8 + // last_heartbeat
+        2 + // reputation_score
+        8 + // total_rewards
+        PerformanceMetrics::LEN;
+}
+
+impl TryFrom<NodeAccountV0> for NodeAccount {
+    type Error = Error;
+
+    fn try_from(v0: NodeAccountV0) -> Result<Self> {
+        let location = Location::try_from(v0.location)?;
+
+        Ok(Self {
+            owner: v0.owner,
+            pending_owner: None,
+            operator_hotkey: None,
+            node_id: v0.node_id,
+            hex_cell: location.hex_cell(),
+            location,
+            // The location was set at registration
+            last_relocated_at: v0.registration_time,
+            hardware_hash: v0.hardware_hash,
+            declared_bandwidth_mbps: 0,
+            hardware_vendor: None,
+            status: v0.status,
+            registration_time: v0.registration_time,
+            last_heartbeat: v0.last_heartbeat,
+            reputation_score: v0.reputation_score,
+            total_rewards: v0.total_rewards,
+            performance_metrics: v0.performance_metrics,
+            recovery_streak: 0,
+            staked_amount: 0,
+            pending_unstake: 0,
+            unstake_requested_at: 0,
+            version: NODE_ACCOUNT_VERSION,
+            index: NodeIndexEntry::default(),
+            authority_suspended: false,
+            bandwidth_updated_at: 0,
+            registration_nonce: 0,
+            reserved: [0; NODE_ACCOUNT_RESERVED_LEN],
+        })
+    }
+}
+
+/// Decodes raw v0 account data, discriminator included, into the current layout
+pub fn migrate_v0(data: &[u8]) -> Result<NodeAccount> {
+    require!(
+        data.len() == NodeAccountV0::LEN,
+        NodeRegistryError::AccountAlreadyMigrated
+    );
+    require!(
+        data[..8] == NodeAccount::DISCRIMINATOR,
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+
+    let v0 = NodeAccountV0::deserialize(&mut &data[8..])
+        .map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))?;
+
+    v0.try_into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{AccountDeserialize, AccountSerialize};
+
+    fn fixture() -> NodeAccountV0 {
+        NodeAccountV0 {
+            owner: Pubkey::new_unique(),
+            node_id: [7; 32],
+            location: LocationV0 {
+                latitude: 40.712776,
+                longitude: -74.005974,
+                country_code: *b"US",
+            },
+            hardware_hash: [9; 32],
+            status: NodeStatus::Maintenance,
+            registration_time: 1_690_000_000,
+            last_heartbeat: 1_700_000_600,
+            reputation_score: 742,
+            total_rewards: 12_345,
+            performance_metrics: PerformanceMetrics {
+                uptime_percentage: 99,
+                data_transferred: 1 << 40,
+                users_served: 321,
+                avg_response_time: 42,
+                reliability_score: 97,
+            },
+        }
+    }
+
+    /// Raw account data as the v0 program allocated and wrote it
+    fn v0_account_data(v0: &NodeAccountV0) -> Vec<u8> {
+        let mut data = vec![0u8; NodeAccountV0::LEN];
+        data[..8].copy_from_slice(&NodeAccount::DISCRIMINATOR);
+        let mut cursor = &mut data[8..];
+        v0.serialize(&mut cursor).unwrap();
+        data
+    }
+
+    fn assert_upgraded(v0: &NodeAccountV0, node: &NodeAccount) {
+        assert_eq!(node.owner, v0.owner);
+        assert_eq!(node.pending_owner, None);
+        assert_eq!(node.operator_hotkey, None);
+        assert_eq!(node.node_id, v0.node_id);
+        assert_eq!(
+            node.location,
+            Location {
+                latitude_e6: 40_712_776,
+                longitude_e6: -74_005_974,
+                country_code: *b"US",
+            }
+        );
+        assert_eq!(node.hex_cell, node.location.hex_cell());
+        assert_eq!(node.last_relocated_at, v0.registration_time);
+        assert_eq!(node.hardware_hash, v0.hardware_hash);
+        assert_eq!(node.declared_bandwidth_mbps, 0);
+        assert_eq!(node.hardware_vendor, None);
+        assert_eq!(node.status, v0.status);
+        assert_eq!(node.registration_time, v0.registration_time);
+        assert_eq!(node.last_heartbeat, v0.last_heartbeat);
+        assert_eq!(node.reputation_score, v0.reputation_score);
+        assert_eq!(node.total_rewards, v0.total_rewards);
+        assert_eq!(
+            node.performance_metrics.data_transferred,
+            v0.performance_metrics.data_transferred
+        );
+        assert_eq!(node.performance_metrics.users_served, v0.performance_metrics.users_served);
+        assert_eq!(node.recovery_streak, 0);
+        assert_eq!(node.staked_amount, 0);
+        assert_eq!(node.pending_unstake, 0);
+        assert_eq!(node.unstake_requested_at, 0);
+        assert_eq!(node.version, NODE_ACCOUNT_VERSION);
+        assert!(!node.index.indexed);
+        assert!(!node.authority_suspended);
+        assert_eq!(node.bandwidth_updated_at, 0);
+        assert_eq!(node.registration_nonce, 0);
+        assert_eq!(node.reserved, [0; NODE_ACCOUNT_RESERVED_LEN]);
+    }
+
+    #[test]
+    fn v0_space_matches_the_deployed_program() {
+        // `NodeAccount::LEN` of the first deployed registry
+        assert_eq!(NodeAccountV0::LEN, 168);
+    }
+
+    #[test]
+    fn migrates_v0_fixture() {
+        let v0 = fixture();
+        let node = migrate_v0(&v0_account_data(&v0)).unwrap();
+
+        assert_upgraded(&v0, &node);
+    }
+
+    #[test]
+    fn rounds_coordinates_to_micro_degrees() {
+        let location = Location::try_from(LocationV0 {
+            latitude: -33.8688197,
+            longitude: 151.2092956,
+            country_code: *b"AU",
+        })
+        .unwrap();
+
+        assert_eq!(location.latitude_e6, -33_868_820);
+        assert_eq!(location.longitude_e6, 151_209_296);
+
+        let pole = Location::try_from(LocationV0 {
+            latitude: 90.0,
+            longitude: -180.0,
+            country_code: *b"AQ",
+        })
+        .unwrap();
+        assert!(pole.is_valid());
+    }
+
+    #[test]
+    fn rejects_coordinates_v0_would_not_register() {
+        for (latitude, longitude) in [(90.5, 0.0), (0.0, -180.5)] {
+            let mut v0 = fixture();
+            v0.location.latitude = latitude;
+            v0.location.longitude = longitude;
+
+            assert_eq!(
+                migrate_v0(&v0_account_data(&v0)).err(),
+                Some(error!(NodeRegistryError::InvalidLocation))
+            );
+        }
+    }
+
+    #[test]
+    fn upgraded_account_round_trips_through_anchor() {
+        let v0 = fixture();
+        let node = migrate_v0(&v0_account_data(&v0)).unwrap();
+
+        let mut data = Vec::with_capacity(NodeAccount::LEN);
+        node.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= NodeAccount::LEN);
+
+        data.resize(NodeAccount::LEN, 0);
+        let decoded = NodeAccount::try_deserialize(&mut data.as_slice()).unwrap();
+        assert_upgraded(&v0, &decoded);
+    }
+
+    #[test]
+    fn current_layout_rejects_v0_data() {
+        let data = v0_account_data(&fixture());
+
+        assert!(NodeAccount::try_deserialize(&mut data.as_slice()).is_err());
+    }
+
+    #[test]
+    fn rejects_already_migrated_data() {
+        let data = vec![0u8; NodeAccount::LEN];
+
+        assert_eq!(
+            migrate_v0(&data).err(),
+            Some(error!(NodeRegistryError::AccountAlreadyMigrated))
+        );
+    }
+
+    #[test]
+    fn rejects_foreign_discriminator() {
+        let mut data = v0_account_data(&fixture());
+        data[..8].copy_from_slice(&NetworkState::DISCRIMINATOR);
+
+        assert_eq!(
+            migrate_v0(&data).err(),
+            Some(error!(ErrorCode::AccountDiscriminatorMismatch))
+        );
+    }
+}
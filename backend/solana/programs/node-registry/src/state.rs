@@ -1,15 +1,29 @@
 use anchor_lang::prelude::*;
+use crate::errors::NodeRegistryError;
+use crate::geo;
 
 #[account]
 pub struct NodeAccount {
     /// The owner/operator of this node
     pub owner: Pubkey,
+    /// Owner proposed by the current owner, pending acceptance
+    pub pending_owner: Option<Pubkey>,
+    /// Delegated key allowed to submit heartbeats and toggle maintenance
+    pub operator_hotkey: Option<Pubkey>,
     /// Unique node identifier
     pub node_id: [u8; 32],
     /// Geographic location of the node
     pub location: Location,
+    /// Hex grid cell of `location`
+    pub hex_cell: u64,
+    /// When the node last changed location
+    pub last_relocated_at: i64,
     /// Hash of hardware specifications
     pub hardware_hash: [u8; 32],
+    /// Uplink bandwidth declared at registration, bounds heartbeat metrics
+    pub declared_bandwidth_mbps: u32,
+    /// Vendor whose signature attested the hardware, `None` if unverified
+    pub hardware_vendor: Option<Pubkey>,
     /// Current status of the node
     pub status: NodeStatus,
     /// When the node was registered
@@ -22,37 +36,113 @@ pub struct NodeAccount {
     pub total_rewards: u64,
     /// Performance metrics
     pub performance_metrics: PerformanceMetrics,
+    /// Consecutive heartbeats at or above `min_reputation` while suspended
+    pub recovery_streak: u16,
+    /// Stake tokens held in the node's stake vault
+    pub staked_amount: u64,
+    /// Portion of the stake waiting out the unstake cooldown
+    pub pending_unstake: u64,
+    /// When the pending unstake was requested
+    pub unstake_requested_at: i64,
+    /// Layout version, see `migration`
+    pub version: u8,
+    /// Where the node is listed in the country and status index pages
+    pub index: NodeIndexEntry,
+    /// Whether the authority imposed the current suspension; such suspensions
+    /// are not lifted by healthy heartbeats
+    pub authority_suspended: bool,
+    /// When `declared_bandwidth_mbps` was last set
+    pub bandwidth_updated_at: i64,
+    /// Number of this registration, from `NetworkState::registrations`; tells
+    /// a re-registered `node_id` apart from its previous registrations
+    pub registration_nonce: u64,
+    /// Space for future fields; new fields are carved from here so that
+    /// zeroed bytes decode as their defaults
+    pub reserved: [u8; NODE_ACCOUNT_RESERVED_LEN],
 }
 
 impl NodeAccount {
     pub const LEN: usize = 8 + // discriminator
         32 + // owner
+        1 + 32 + // pending_owner
+        1 + 32 + // operator_hotkey
         32 + // node_id
         Location::LEN +
+        8 + // hex_cell
+        8 + // last_relocated_at
         32 + // hardware_hash
+        4 + // declared_bandwidth_mbps
+        1 + 32 + // hardware_vendor
         NodeStatus::LEN +
         8 + // registration_time
         8 + // last_heartbeat
         2 + // reputation_score
         8 + // total_rewards
-        PerformanceMetrics::LEN;
+        PerformanceMetrics::LEN +
+        2 + // recovery_streak
+        8 + // staked_amount
+        8 + // pending_unstake
+        8 + // unstake_requested_at
+        1 + // version
+        NodeIndexEntry::LEN +
+        1 + // authority_suspended
+        8 + // bandwidth_updated_at
+        8 + // registration_nonce
+        NODE_ACCOUNT_RESERVED_LEN; // reserved
+
+    /// Whether the node is suspended; such nodes keep their stake and
+    /// registration until the suspension is lifted
+    pub fn is_suspended(&self) -> bool {
+        self.status == NodeStatus::Suspended || self.authority_suspended
+    }
+
+    /// Whether `key` may act as the node's operator (owner or hotkey)
+    pub fn is_operator(&self, key: &Pubkey) -> bool {
+        self.owner == *key || self.operator_hotkey.as_ref() == Some(key)
+    }
+
+    /// Whether an approved vendor attested the node's hardware
+    pub fn is_hardware_verified(&self) -> bool {
+        self.hardware_vendor.is_some()
+    }
+
+    /// Whether `mbps` is a usable declared bandwidth; zero would reject every
+    /// heartbeat with traffic, and no bound would make the plausibility checks moot
+    pub fn is_valid_declared_bandwidth(mbps: u32) -> bool {
+        (1..=MAX_DECLARED_BANDWIDTH_MBPS).contains(&mbps)
+    }
+
+    /// Stake still bonded to the node, excluding any pending unstake
+    pub fn bonded_stake(&self) -> u64 {
+        self.staked_amount.saturating_sub(self.pending_unstake)
+    }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
 pub struct Location {
-    /// Latitude in degrees (-90 to 90)
-    pub latitude: f64,
-    /// Longitude in degrees (-180 to 180)
-    pub longitude: f64,
+    /// Latitude in micro-degrees (-90_000_000 to 90_000_000)
+    pub latitude_e6: i32,
+    /// Longitude in micro-degrees (-180_000_000 to 180_000_000)
+    pub longitude_e6: i32,
     /// Country code (ISO 3166-1 alpha-2)
     pub country_code: [u8; 2],
 }
 
 impl Location {
-    pub const LEN: usize = 8 + 8 + 2; // latitude + longitude + country_code
+    pub const LEN: usize = 4 + 4 + 2; // latitude_e6 + longitude_e6 + country_code
+
+    pub fn is_valid(&self) -> bool {
+        geo::is_valid_coordinate(self.latitude_e6, self.longitude_e6)
+            && self.country_code.iter().all(u8::is_ascii_uppercase)
+    }
+
+    /// Canonical spatial key shared by coverage, rewards and the map
+    pub fn hex_cell(&self) -> u64 {
+        geo::hex_cell(self.latitude_e6, self.longitude_e6)
+    }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
 pub enum NodeStatus {
     /// Node is active and serving users
     Active,
@@ -66,6 +156,150 @@ pub enum NodeStatus {
 
 impl NodeStatus {
     pub const LEN: usize = 1 + 1; // enum discriminator + largest variant
+
+    /// Transition table: whether `actor` may move a node from `self` to `new_status`
+    pub fn can_transition_to(&self, new_status: NodeStatus, actor: StatusChangeActor) -> bool {
+        use NodeStatus::*;
+
+        if *self == new_status {
+            return false;
+        }
+
+        match actor {
+            // Owners move freely between the operational states
+            StatusChangeActor::Owner => matches!(
+                (self, new_status),
+                (Active | Inactive | Maintenance, Active | Inactive | Maintenance)
+            ),
+            // Hotkeys may only toggle maintenance
+            StatusChangeActor::Hotkey => matches!(
+                (self, new_status),
+                (Active | Inactive, Maintenance) | (Maintenance, Active | Inactive)
+            ),
+            // Only the authority or an automatic rule enters or lifts a suspension
+            StatusChangeActor::Authority => matches!(
+                (self, new_status),
+                (Active | Inactive | Maintenance, Suspended) | (Suspended, Inactive)
+            ),
+            // Automatic rules may additionally retire nodes that stopped heartbeating
+            StatusChangeActor::Automatic => matches!(
+                (self, new_status),
+                (Active | Inactive | Maintenance, Suspended)
+                    | (Suspended, Inactive)
+                    | (Active, Inactive)
+            ),
+        }
+    }
+}
+
+/// A node's position in the index pages. Zeroed bytes decode as unindexed,
+/// which is how accounts carved from `reserved` start out.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct NodeIndexEntry {
+    /// Whether the node is listed at all
+    pub indexed: bool,
+    /// Page of the node's country index holding it
+    pub country_page: u32,
+    /// Status the node is listed under; lags `status` after automatic
+    /// transitions until `sync_node_index` runs
+    pub status: NodeStatus,
+    /// Page of that status index holding it
+    pub status_page: u32,
+}
+
+impl NodeIndexEntry {
+    pub const LEN: usize = 1 + 4 + 1 + 4; // indexed + country_page + status + status_page
+}
+
+impl Default for NodeIndexEntry {
+    fn default() -> Self {
+        Self {
+            indexed: false,
+            country_page: 0,
+            status: NodeStatus::Active,
+            status_page: 0,
+        }
+    }
+}
+
+/// What a `NodeIndexPage` lists nodes by
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum IndexKey {
+    /// Nodes located in a country (ISO 3166-1 alpha-2)
+    Country([u8; 2]),
+    /// Nodes in a status
+    Status(NodeStatus),
+}
+
+impl IndexKey {
+    pub const LEN: usize = 1 + 2; // enum discriminator + largest variant
+
+    /// Fixed-width seed component identifying the key in page PDAs
+    pub fn seed(&self) -> [u8; 3] {
+        match self {
+            IndexKey::Country(code) => [0, code[0], code[1]],
+            IndexKey::Status(status) => [1, *status as u8, 0],
+        }
+    }
+}
+
+/// One page of node pubkeys sharing an `IndexKey`, at
+/// `[b"node_index", key.seed(), page.to_le_bytes()]`. Pages are created in
+/// order from 0, so clients walk them until the first missing page.
+#[account]
+pub struct NodeIndexPage {
+    /// What this page lists nodes by
+    pub key: IndexKey,
+    /// Position of this page in the key's sequence
+    pub page: u32,
+    /// Listed nodes, unordered
+    pub nodes: Vec<Pubkey>,
+}
+
+impl NodeIndexPage {
+    pub const LEN: usize = 8 + // discriminator
+        IndexKey::LEN + // key
+        4 + // page
+        4 + 32 * NODE_INDEX_PAGE_CAPACITY; // nodes
+
+    pub fn insert(&mut self, node: Pubkey) -> Result<()> {
+        require!(
+            self.nodes.len() < NODE_INDEX_PAGE_CAPACITY,
+            NodeRegistryError::IndexPageFull
+        );
+        self.nodes.push(node);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, node: &Pubkey) -> Result<()> {
+        let position = self
+            .nodes
+            .iter()
+            .position(|listed| listed == node)
+            .ok_or(NodeRegistryError::IndexPageMismatch)?;
+        self.nodes.swap_remove(position);
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum SlashReason {
+    /// Node failed its coverage obligations
+    FailedCoverage,
+    /// Node submitted fraudulent data
+    Fraud,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum StatusChangeActor {
+    /// The node owner
+    Owner,
+    /// The node's delegated operator hotkey
+    Hotkey,
+    /// The network authority
+    Authority,
+    /// A program rule such as reputation-based suspension
+    Automatic,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
@@ -86,6 +320,154 @@ impl PerformanceMetrics {
     pub const LEN: usize = 1 + 8 + 4 + 4 + 1;
 }
 
+#[account]
+pub struct PerformanceHistory {
+    /// Node account this history belongs to
+    pub node_account: Pubkey,
+    /// Slot in `samples` the next heartbeat overwrites once the buffer is full
+    pub next_index: u16,
+    /// Ring buffer of the most recent heartbeats
+    pub samples: Vec<HeartbeatSample>,
+}
+
+impl PerformanceHistory {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // node_account
+        2 + // next_index
+        4 + HeartbeatSample::LEN * PERFORMANCE_HISTORY_LEN; // samples
+
+    /// Append a sample, overwriting the oldest once the buffer is full
+    pub fn record(&mut self, sample: HeartbeatSample) {
+        if self.samples.len() < PERFORMANCE_HISTORY_LEN {
+            self.samples.push(sample);
+        } else {
+            self.samples[self.next_index as usize] = sample;
+        }
+        self.next_index = ((self.next_index as usize + 1) % PERFORMANCE_HISTORY_LEN) as u16;
+    }
+
+    /// Samples ordered from oldest to newest
+    pub fn chronological(&self) -> impl Iterator<Item = &HeartbeatSample> {
+        let split = if self.samples.len() < PERFORMANCE_HISTORY_LEN {
+            0
+        } else {
+            self.next_index as usize
+        };
+        self.samples[split..].iter().chain(self.samples[..split].iter())
+    }
+
+    /// Exponentially weighted reputation over the history, newest samples weighing most
+    pub fn weighted_reputation(&self) -> u16 {
+        let mut samples = self.chronological();
+        let mut reputation = match samples.next() {
+            Some(sample) => sample.reputation() as u32,
+            None => return 0,
+        };
+
+        for sample in samples {
+            reputation = (reputation * (BPS_DENOMINATOR - REPUTATION_EWMA_ALPHA_BPS)
+                + sample.reputation() as u32 * REPUTATION_EWMA_ALPHA_BPS)
+                / BPS_DENOMINATOR;
+        }
+
+        reputation.min(MAX_REPUTATION as u32) as u16
+    }
+
+    pub fn summary(&self) -> PerformanceSummary {
+        let sample_count = self.samples.len() as u64;
+        let mut summary = PerformanceSummary {
+            node_account: self.node_account,
+            sample_count: sample_count as u16,
+            reputation_score: self.weighted_reputation(),
+            ..PerformanceSummary::default()
+        };
+
+        if sample_count == 0 {
+            return summary;
+        }
+
+        let (mut uptime, mut reliability, mut response_time, mut users) = (0u64, 0u64, 0u64, 0u64);
+        for sample in &self.samples {
+            uptime += sample.uptime_percentage as u64;
+            reliability += sample.reliability_score as u64;
+            response_time += sample.avg_response_time as u64;
+            users += sample.users_served as u64;
+        }
+
+        let mut chronological = self.chronological();
+        let oldest = chronological.next().unwrap();
+        let newest = chronological.last().unwrap_or(oldest);
+
+        summary.window_start = oldest.timestamp;
+        summary.window_end = newest.timestamp;
+        summary.avg_uptime_percentage = (uptime / sample_count) as u8;
+        summary.avg_reliability_score = (reliability / sample_count) as u8;
+        summary.avg_response_time = (response_time / sample_count) as u32;
+        summary.avg_users_served = (users / sample_count) as u32;
+        // Data counters are cumulative, so the window's traffic is the difference
+        summary.data_transferred = newest.data_transferred.saturating_sub(oldest.data_transferred);
+
+        summary
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct HeartbeatSample {
+    /// When the heartbeat was recorded
+    pub timestamp: i64,
+    /// Uptime percentage (0-100)
+    pub uptime_percentage: u8,
+    /// Reliability score (0-100)
+    pub reliability_score: u8,
+    /// Cumulative data transferred in bytes
+    pub data_transferred: u64,
+    /// Number of users served
+    pub users_served: u32,
+    /// Average response time in milliseconds
+    pub avg_response_time: u32,
+}
+
+impl HeartbeatSample {
+    pub const LEN: usize = 8 + 1 + 1 + 8 + 4 + 4;
+
+    pub fn new(timestamp: i64, metrics: &PerformanceMetrics) -> Self {
+        Self {
+            timestamp,
+            uptime_percentage: metrics.uptime_percentage,
+            reliability_score: metrics.reliability_score,
+            data_transferred: metrics.data_transferred,
+            users_served: metrics.users_served,
+            avg_response_time: metrics.avg_response_time,
+        }
+    }
+
+    /// Reputation implied by this sample alone
+    pub fn reputation(&self) -> u16 {
+        let uptime_score = (self.uptime_percentage as u16) * 5; // 0-500 points
+        let reliability_score = (self.reliability_score as u16) * 5; // 0-500 points
+
+        // Average the scores and ensure within bounds
+        let reputation = (uptime_score + reliability_score) / 2;
+        reputation.min(MAX_REPUTATION)
+    }
+}
+
+/// Aggregates over a node's performance history, returned by `get_performance_summary`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct PerformanceSummary {
+    pub node_account: Pubkey,
+    pub sample_count: u16,
+    pub window_start: i64,
+    pub window_end: i64,
+    pub avg_uptime_percentage: u8,
+    pub avg_reliability_score: u8,
+    pub avg_response_time: u32,
+    pub avg_users_served: u32,
+    /// Bytes transferred between the oldest and newest sample
+    pub data_transferred: u64,
+    pub reputation_score: u16,
+}
+
 #[account]
 pub struct NetworkState {
     /// Authority that can update network parameters
@@ -96,8 +478,29 @@ pub struct NetworkState {
     pub active_nodes: u32,
     /// Minimum reputation score required
     pub min_reputation: u16,
+    /// Consecutive healthy heartbeats a suspended node needs to recover
+    pub recovery_heartbeats: u16,
+    /// Minimum seconds between two heartbeats from the same node
+    pub min_heartbeat_interval: i64,
+    /// Seconds without a heartbeat after which an active node is stale
+    pub heartbeat_timeout: i64,
+    /// Lamports paid to the cranker per stale node marked inactive, out of
+    /// the pool deposited through `fund_stale_bounty`
+    pub stale_bounty: u64,
+    /// Mint of the token operators stake
+    pub stake_mint: Pubkey,
+    /// Minimum bonded stake required to register and stay active
+    pub min_stake: u64,
+    /// Seconds between an unstake request and its withdrawal
+    pub unstake_cooldown: i64,
+    /// Seconds a node must wait between relocations
+    pub relocation_cooldown: i64,
     /// Reward parameters
     pub reward_params: RewardParams,
+    /// Authority proposed by the current authority, pending acceptance
+    pub pending_authority: Option<Pubkey>,
+    /// Registrations made so far, numbers each new registration
+    pub registrations: u64,
 }
 
 impl NetworkState {
@@ -106,7 +509,54 @@ impl NetworkState {
         4 + // total_nodes
         4 + // active_nodes
         2 + // min_reputation
-        RewardParams::LEN;
+        2 + // recovery_heartbeats
+        8 + // min_heartbeat_interval
+        8 + // heartbeat_timeout
+        8 + // stale_bounty
+        32 + // stake_mint
+        8 + // min_stake
+        8 + // unstake_cooldown
+        8 + // relocation_cooldown
+        RewardParams::LEN +
+        1 + 32 + // pending_authority
+        8; // registrations
+}
+
+#[account]
+pub struct VendorRegistry {
+    /// Approved hardware-vendor attestation keys
+    pub vendors: Vec<Pubkey>,
+}
+
+impl VendorRegistry {
+    pub const LEN: usize = 8 + // discriminator
+        4 + 32 * MAX_HARDWARE_VENDORS; // vendors
+
+    pub fn is_approved(&self, vendor: &Pubkey) -> bool {
+        self.vendors.contains(vendor)
+    }
+}
+
+/// Binds a `hardware_hash` to the one node registered with it, at
+/// `[b"hardware", hardware_hash]`
+#[account]
+pub struct HardwareClaim {
+    /// Node holding the claim, default while the claim is being created
+    pub node: Pubkey,
+    /// Claimed hardware
+    pub hardware_hash: [u8; 32],
+    /// Who paid the claim's rent and receives it back on release
+    pub rent_payer: Pubkey,
+    /// When the claim was made
+    pub claimed_at: i64,
+}
+
+impl HardwareClaim {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // node
+        32 + // hardware_hash
+        32 + // rent_payer
+        8; // claimed_at
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
@@ -124,3 +574,56 @@ pub struct RewardParams {
 impl RewardParams {
     pub const LEN: usize = 8 + 2 + 8 + 8;
 }
+
+/// Current `NodeAccount` layout version
+pub const NODE_ACCOUNT_VERSION: u8 = 1;
+
+/// Length of `NodeAccount::reserved`
+pub const NODE_ACCOUNT_RESERVED_LEN: usize = 101;
+
+/// Nodes listed per `NodeIndexPage`
+pub const NODE_INDEX_PAGE_CAPACITY: usize = 64;
+
+/// Maximum number of approved hardware vendors
+pub const MAX_HARDWARE_VENDORS: usize = 16;
+
+/// Number of heartbeats kept in each node's performance history
+pub const PERFORMANCE_HISTORY_LEN: usize = 32;
+
+/// Weight of the newest sample in the reputation average (basis points)
+pub const REPUTATION_EWMA_ALPHA_BPS: u32 = 2_500;
+
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Upper bound of `NodeAccount::reputation_score`
+pub const MAX_REPUTATION: u16 = 1000;
+
+/// Default number of healthy heartbeats required to leave suspension
+pub const DEFAULT_RECOVERY_HEARTBEATS: u16 = 24;
+
+/// Default minimum seconds between heartbeats
+pub const DEFAULT_MIN_HEARTBEAT_INTERVAL: i64 = 60;
+
+/// Highest bandwidth a node may declare (100 Gbps)
+pub const MAX_DECLARED_BANDWIDTH_MBPS: u32 = 100_000;
+
+/// Seconds a node must wait between changes to its declared bandwidth
+pub const BANDWIDTH_UPDATE_COOLDOWN: i64 = 7 * 24 * 60 * 60;
+
+/// Bytes per second one Mbps of declared bandwidth can carry
+pub const BYTES_PER_SECOND_PER_MBPS: u64 = 125_000;
+
+/// Users a node may plausibly serve per Mbps of declared bandwidth
+pub const MAX_USERS_PER_MBPS: u64 = 50;
+
+/// Highest plausible average response time in milliseconds
+pub const MAX_RESPONSE_TIME_MS: u32 = 60_000;
+
+/// Default seconds without a heartbeat before a node is considered stale
+pub const DEFAULT_HEARTBEAT_TIMEOUT: i64 = 60 * 60;
+
+/// Default seconds between node relocations
+pub const DEFAULT_RELOCATION_COOLDOWN: i64 = 7 * 24 * 60 * 60;
+
+/// Default lamports paid per stale node to the `mark_stale` cranker
+pub const DEFAULT_STALE_BOUNTY: u64 = 5_000;
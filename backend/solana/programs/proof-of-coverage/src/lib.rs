@@ -1,8 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock::Clock;
-use std::collections::HashMap;
+use thepublic_node_registry::geo;
+use thepublic_node_registry::state::Location;
 
-declare_id!("ProofCoverageProgram1111111111111111111111");
+declare_id!("ProofCoverageProgram11111111111111111111111");
 
 #[program]
 pub mod proof_of_coverage {
@@ -26,8 +27,11 @@ pub mod proof_of_coverage {
         let node_account = &mut ctx.accounts.node_account;
         let coverage_state = &mut ctx.accounts.coverage_state;
 
+        require!(location.is_valid(), CoverageError::InvalidLocation);
+
         node_account.owner = ctx.accounts.owner.key();
-        node_account.location = location;
+        node_account.hex_cell = location.hex_cell();
+        node_account.location = location.clone();
         node_account.hardware_specs = hardware_specs;
         node_account.status = NodeStatus::Active;
         node_account.uptime_percentage = 0.0;
@@ -41,7 +45,8 @@ pub mod proof_of_coverage {
         emit!(NodeRegistered {
             node_id: node_account.key(),
             owner: ctx.accounts.owner.key(),
-            location: location,
+            location,
+            hex_cell: node_account.hex_cell,
         });
 
         Ok(())
@@ -113,7 +118,7 @@ pub mod proof_of_coverage {
         let clock = Clock::get()?;
 
         challenge_account.target_node = target_node;
-        challenge_account.challenge_type = challenge_type;
+        challenge_account.challenge_type = challenge_type.clone();
         challenge_account.issued_at = clock.unix_timestamp;
         challenge_account.expires_at = clock.unix_timestamp + 300; // 5 minutes to respond
         challenge_account.status = ChallengeStatus::Pending;
@@ -136,10 +141,10 @@ pub mod proof_of_coverage {
         let coverage_state = &mut ctx.accounts.coverage_state;
         
         // Calculate coverage density for the region
-        let coverage_density = calculate_region_coverage(&region, &ctx.remaining_accounts)?;
+        let coverage_density = calculate_region_coverage(&region, ctx.remaining_accounts)?;
         
         // Update regional coverage statistics
-        update_regional_stats(coverage_state, region, coverage_density);
+        update_regional_stats(coverage_state, &region, coverage_density);
 
         emit!(NetworkCoverageVerified {
             region,
@@ -171,7 +176,16 @@ fn verify_location_proof(proof: &ProofData, claimed_location: &Location) -> Resu
     // Verify the node is actually at the claimed location
     // This could use GPS data, triangulation, or other methods
     
-    let distance = calculate_distance(&proof.gps_coordinates, &claimed_location.coordinates);
+    require!(
+        geo::is_valid_coordinate(proof.gps_coordinates.latitude_e6, proof.gps_coordinates.longitude_e6),
+        CoverageError::InvalidLocation
+    );
+
+    let claimed = Coordinates {
+        latitude_e6: claimed_location.latitude_e6,
+        longitude_e6: claimed_location.longitude_e6,
+    };
+    let distance = calculate_distance(&proof.gps_coordinates, &claimed);
     Ok(distance < 100.0) // Allow 100m variance
 }
 
@@ -207,11 +221,11 @@ fn calculate_region_coverage(region: &Region, nodes: &[AccountInfo]) -> Result<f
     Ok((active_nodes as f64 / required_nodes as f64).min(1.0) * 100.0)
 }
 
-fn count_active_nodes_in_region(region: &Region, nodes: &[AccountInfo]) -> Result<u32> {
+fn count_active_nodes_in_region(_region: &Region, nodes: &[AccountInfo]) -> Result<u32> {
     // Count active nodes within the region boundaries
     let mut count = 0;
     
-    for node_info in nodes {
+    for _node_info in nodes {
         // Parse node account and check if it's in the region
         // This is simplified - real implementation would deserialize accounts
         count += 1;
@@ -233,9 +247,9 @@ fn calculate_required_nodes(region: &Region) -> u32 {
 }
 
 fn update_regional_stats(
-    coverage_state: &mut CoverageState,
-    region: Region,
-    coverage_density: f64,
+    _coverage_state: &mut CoverageState,
+    _region: &Region,
+    _coverage_density: f64,
 ) {
     // Update regional coverage statistics
     // This would store regional data in the state
@@ -243,10 +257,12 @@ fn update_regional_stats(
 
 fn calculate_distance(coord1: &Coordinates, coord2: &Coordinates) -> f64 {
     // Haversine formula for calculating distance between two GPS coordinates
-    let lat1_rad = coord1.latitude.to_radians();
-    let lat2_rad = coord2.latitude.to_radians();
-    let delta_lat = (coord2.latitude - coord1.latitude).to_radians();
-    let delta_lon = (coord2.longitude - coord1.longitude).to_radians();
+    let (lat1, lon1) = coord1.degrees();
+    let (lat2, lon2) = coord2.degrees();
+    let lat1_rad = lat1.to_radians();
+    let lat2_rad = lat2.to_radians();
+    let delta_lat = (lat2 - lat1).to_radians();
+    let delta_lon = (lon2 - lon1).to_radians();
 
     let a = (delta_lat / 2.0).sin().powi(2)
         + lat1_rad.cos() * lat2_rad.cos() * (delta_lon / 2.0).sin().powi(2);
@@ -266,7 +282,10 @@ pub struct CoverageState {
 #[account]
 pub struct NodeAccount {
     pub owner: Pubkey,
+    /// Same fixed-point layout as the node registry's `Location`
     pub location: Location,
+    /// Canonical spatial key of `location`, shared with the node registry
+    pub hex_cell: u64,
     pub hardware_specs: HardwareSpecs,
     pub status: NodeStatus,
     pub uptime_percentage: f64,
@@ -286,17 +305,18 @@ pub struct ChallengeAccount {
 }
 
 // Data structures
+/// Fixed-point coordinates in micro-degrees, as in the node registry
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct Location {
-    pub coordinates: Coordinates,
-    pub region: String,
-    pub country: String,
+pub struct Coordinates {
+    pub latitude_e6: i32,
+    pub longitude_e6: i32,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct Coordinates {
-    pub latitude: f64,
-    pub longitude: f64,
+impl Coordinates {
+    /// Latitude and longitude in degrees
+    pub fn degrees(&self) -> (f64, f64) {
+        (self.latitude_e6 as f64 / 1e6, self.longitude_e6 as f64 / 1e6)
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -409,6 +429,7 @@ pub struct NodeRegistered {
     pub node_id: Pubkey,
     pub owner: Pubkey,
     pub location: Location,
+    pub hex_cell: u64,
 }
 
 #[event]
@@ -448,3 +469,54 @@ pub enum CoverageError {
     #[msg("Invalid location verification")]
     InvalidLocation,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location() -> Location {
+        Location {
+            latitude_e6: 40_712_776,
+            longitude_e6: -74_005_974,
+            country_code: *b"US",
+        }
+    }
+
+    fn proof_at(latitude_e6: i32, longitude_e6: i32) -> ProofData {
+        ProofData {
+            timestamp: 0,
+            signal_strength: 80.0,
+            average_latency: 20.0,
+            gps_coordinates: Coordinates { latitude_e6, longitude_e6 },
+            network_measurements: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn location_proof_allows_100m_of_drift() {
+        let claimed = location();
+
+        assert!(verify_location_proof(&proof_at(40_712_776, -74_005_974), &claimed).unwrap());
+        // 500 micro-degrees of latitude is about 56m
+        assert!(verify_location_proof(&proof_at(40_713_276, -74_005_974), &claimed).unwrap());
+        // 1000 is about 111m
+        assert!(!verify_location_proof(&proof_at(40_713_776, -74_005_974), &claimed).unwrap());
+    }
+
+    #[test]
+    fn location_proof_rejects_invalid_coordinates() {
+        assert_eq!(
+            verify_location_proof(&proof_at(90_000_001, 0), &location()).err(),
+            Some(error!(CoverageError::InvalidLocation))
+        );
+    }
+
+    #[test]
+    fn distance_uses_fixed_point_coordinates() {
+        let new_york = Coordinates { latitude_e6: 40_712_776, longitude_e6: -74_005_974 };
+        let london = Coordinates { latitude_e6: 51_507_351, longitude_e6: -127_758 };
+        let distance = calculate_distance(&new_york, &london);
+
+        assert!((5_570_000.0..5_571_000.0).contains(&distance), "{}", distance);
+    }
+}
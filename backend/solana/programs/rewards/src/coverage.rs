@@ -0,0 +1,81 @@
+//! Read-only view of proof-of-coverage node accounts.
+
+use anchor_lang::prelude::*;
+use crate::errors::*;
+use crate::state::BPS_DENOMINATOR;
+
+pub use thepublic_proof_of_coverage::{
+    NodeAccount as CoverageNode,
+    NodeStatus as CoverageNodeStatus,
+    ID as PROOF_OF_COVERAGE_PROGRAM_ID,
+};
+
+/// Loads a proof-of-coverage node account, checking owner and discriminator
+pub fn load_coverage_node(info: &AccountInfo) -> Result<CoverageNode> {
+    // Anchor discriminators only depend on the type name, which the registry
+    // also calls `NodeAccount`, so the owner is what tells them apart
+    require_keys_eq!(
+        *info.owner,
+        PROOF_OF_COVERAGE_PROGRAM_ID,
+        RewardsError::InvalidCoverageAccount
+    );
+
+    let data = info.try_borrow_data()?;
+    CoverageNode::try_deserialize(&mut &data[..])
+        .map_err(|_| error!(RewardsError::InvalidCoverageAccount))
+}
+
+/// Share of the node's coverage challenges it passed, in basis points.
+/// Taken from the integer counters rather than the program's `f64` score.
+pub fn coverage_score_bps(node: &CoverageNode) -> u16 {
+    let passed = node.challenges_passed as u128;
+    let total = passed + node.challenges_failed as u128;
+    if total == 0 {
+        return 0;
+    }
+
+    (passed * BPS_DENOMINATOR as u128 / total) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use thepublic_node_registry::state::Location;
+    use thepublic_proof_of_coverage::HardwareSpecs;
+
+    fn coverage_node(challenges_passed: u64, challenges_failed: u64) -> CoverageNode {
+        CoverageNode {
+            owner: Pubkey::new_unique(),
+            location: Location {
+                latitude_e6: 0,
+                longitude_e6: 0,
+                country_code: *b"US",
+            },
+            hex_cell: 0,
+            hardware_specs: HardwareSpecs {
+                cpu_cores: 4,
+                memory_gb: 8,
+                storage_gb: 256,
+                bandwidth_mbps: 100,
+            },
+            status: CoverageNodeStatus::Active,
+            uptime_percentage: 100.0,
+            last_challenge: 0,
+            challenges_passed,
+            challenges_failed,
+            // Ignored, only the counters are scored
+            coverage_score: f64::NAN,
+        }
+    }
+
+    #[test]
+    fn scores_challenge_pass_rate() {
+        assert_eq!(coverage_score_bps(&coverage_node(0, 0)), 0);
+        assert_eq!(coverage_score_bps(&coverage_node(0, 5)), 0);
+        assert_eq!(coverage_score_bps(&coverage_node(3, 1)), 7_500);
+        // Rounds down
+        assert_eq!(coverage_score_bps(&coverage_node(2, 1)), 6_666);
+        assert_eq!(coverage_score_bps(&coverage_node(9, 0)), 10_000);
+        assert_eq!(coverage_score_bps(&coverage_node(u64::MAX, u64::MAX)), 5_000);
+    }
+}
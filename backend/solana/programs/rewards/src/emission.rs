@@ -0,0 +1,129 @@
+//! Per-epoch emission caps that decay geometrically.
+//!
+//! Periods are grouped into epochs of `EmissionSchedule::epoch_periods`. The
+//! first epoch may emit `initial_epoch_emission`, and each later epoch's cap
+//! is the previous one reduced by `decay_bps` (5000 halves it every epoch).
+//! Emissions over the program's lifetime never exceed `max_total_emission`,
+//! and none are made unless the vault covers them on top of everything
+//! distributed earlier but not yet paid out.
+
+use crate::state::*;
+
+/// Fixed-point scale for the compounded decay factor
+const DECAY_SCALE: u128 = 1_000_000_000_000_000_000;
+
+impl EmissionSchedule {
+    pub fn is_valid(&self) -> bool {
+        self.epoch_periods > 0 && self.decay_bps as u64 <= BPS_DENOMINATOR
+    }
+
+    /// Epoch containing `period`
+    pub fn epoch_of(&self, period: u64) -> u64 {
+        period / self.epoch_periods
+    }
+
+    /// Emission cap of `epoch`, `initial_epoch_emission * (1 - decay)^epoch`
+    /// rounded down
+    pub fn epoch_cap(&self, epoch: u64) -> u64 {
+        let mut base = (BPS_DENOMINATOR - self.decay_bps as u64) as u128 * DECAY_SCALE
+            / BPS_DENOMINATOR as u128;
+        let mut factor = DECAY_SCALE;
+        let mut remaining = epoch;
+
+        // Square-and-multiply keeps this logarithmic in the epoch number
+        while remaining > 0 && factor > 0 {
+            if remaining & 1 == 1 {
+                factor = factor * base / DECAY_SCALE;
+            }
+            base = base * base / DECAY_SCALE;
+            remaining >>= 1;
+        }
+
+        (self.initial_epoch_emission as u128 * factor / DECAY_SCALE) as u64
+    }
+}
+
+impl RewardsState {
+    /// Whether a vault holding `vault_amount` still covers every distributed
+    /// but unpaid reward once `amount` more is distributed
+    pub fn vault_covers(&self, vault_amount: u64, amount: u64) -> bool {
+        let outstanding = self.total_distributed.saturating_sub(self.total_paid);
+
+        outstanding as u128 + amount as u128 <= vault_amount as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(decay_bps: u16) -> EmissionSchedule {
+        EmissionSchedule {
+            initial_epoch_emission: 1_000_000,
+            decay_bps,
+            epoch_periods: 52,
+            max_total_emission: 10_000_000,
+        }
+    }
+
+    #[test]
+    fn periods_group_into_epochs() {
+        let schedule = schedule(5_000);
+
+        assert_eq!(schedule.epoch_of(0), 0);
+        assert_eq!(schedule.epoch_of(51), 0);
+        assert_eq!(schedule.epoch_of(52), 1);
+        assert_eq!(schedule.epoch_of(520), 10);
+    }
+
+    #[test]
+    fn halving_halves_each_epoch() {
+        let schedule = schedule(5_000);
+        let caps: Vec<u64> = (0..5).map(|epoch| schedule.epoch_cap(epoch)).collect();
+
+        assert_eq!(caps, [1_000_000, 500_000, 250_000, 125_000, 62_500]);
+        assert_eq!(schedule.epoch_cap(20), 0);
+        assert_eq!(schedule.epoch_cap(u64::MAX), 0);
+    }
+
+    #[test]
+    fn decay_compounds() {
+        let schedule = schedule(1_000);
+
+        assert_eq!(schedule.epoch_cap(1), 900_000);
+        assert_eq!(schedule.epoch_cap(2), 810_000);
+        assert_eq!(schedule.epoch_cap(10), 348_678);
+        // No decay keeps the cap flat, full decay stops emission after epoch 0
+        assert_eq!(self::schedule(0).epoch_cap(1_000), 1_000_000);
+        assert_eq!(self::schedule(10_000).epoch_cap(1), 0);
+    }
+
+    #[test]
+    fn rejects_invalid_schedules() {
+        assert!(schedule(10_000).is_valid());
+        assert!(!schedule(10_001).is_valid());
+        assert!(!EmissionSchedule { epoch_periods: 0, ..schedule(5_000) }.is_valid());
+    }
+
+    #[test]
+    fn vault_must_cover_unpaid_distributions() {
+        let mut state = RewardsState {
+            emission_schedule: schedule(5_000),
+            ..RewardsState::default()
+        };
+
+        assert!(state.vault_covers(1_000, 1_000));
+        assert!(!state.vault_covers(999, 1_000));
+
+        // Finalized but unclaimed rewards stay reserved in the vault
+        state.total_distributed = 1_000;
+        assert!(!state.vault_covers(1_000, 1));
+        assert!(state.vault_covers(1_500, 500));
+
+        // Payouts release the reservation along with the tokens
+        state.total_paid = 600;
+        assert!(state.vault_covers(900, 500));
+        assert!(!state.vault_covers(900, 501));
+        assert!(state.vault_covers(u64::MAX, u64::MAX - 400));
+    }
+}
@@ -18,4 +18,58 @@ pub enum RewardsError {
     NodeNotEligible,
     #[msg("Distribution already completed")]
     DistributionAlreadyCompleted,
+    #[msg("Node reward account does not belong to the node")]
+    NodeRewardAccountMismatch,
+    #[msg("Distribution has too many nodes")]
+    DistributionTooLarge,
+    #[msg("Claim index out of range")]
+    InvalidClaimIndex,
+    #[msg("Reward already claimed")]
+    AlreadyClaimed,
+    #[msg("Invalid merkle proof")]
+    InvalidMerkleProof,
+    #[msg("Claims exceed the distribution total")]
+    ClaimExceedsDistribution,
+    #[msg("Invalid proof-of-coverage node account")]
+    InvalidCoverageAccount,
+    #[msg("Coverage node does not match the node's owner and cell")]
+    CoverageNodeMismatch,
+    #[msg("Invalid reward parameters")]
+    InvalidRewardParams,
+    #[msg("Vesting schedule account required for locked rewards")]
+    VestingScheduleRequired,
+    #[msg("Nothing vested to claim")]
+    NothingVested,
+    #[msg("Invalid emission schedule")]
+    InvalidEmissionSchedule,
+    #[msg("Distribution exceeds the epoch's remaining emission")]
+    EpochEmissionExceeded,
+    #[msg("Distribution exceeds the total emission cap")]
+    TotalEmissionExceeded,
+    #[msg("Invalid reward split")]
+    InvalidRewardSplit,
+    #[msg("Reward split is locked")]
+    RewardSplitLocked,
+    #[msg("Reward split account required")]
+    RewardSplitRequired,
+    #[msg("Beneficiary token accounts do not match the reward split")]
+    BeneficiaryAccountMismatch,
+    #[msg("Split rewards cannot be restaked")]
+    RewardSplitActive,
+    #[msg("Program is paused")]
+    ProgramPaused,
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+    #[msg("Signer is neither the authority nor the guardian")]
+    NotAuthorityOrGuardian,
+    #[msg("Signer is not the node owner")]
+    NotNodeOwner,
+    #[msg("Claim exceeds the node's calculated reward")]
+    ClaimExceedsCalculatedReward,
+    #[msg("Vesting schedule account passed for a claim with nothing to lock")]
+    UnexpectedVestingSchedule,
+    #[msg("Coverage node is not active")]
+    CoverageNodeNotActive,
+    #[msg("Reward account belongs to a different registration of the node")]
+    RegistrationMismatch,
 }
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::formula::RewardInputs;
+use crate::state::Beneficiary;
+
+#[event]
+pub struct NodeRewardCalculated {
+    pub node: Pubkey,
+    pub period: u64,
+    pub inputs: RewardInputs,
+    pub reward: u64,
+}
+
+/// Beneficiaries of a node's claims replaced
+#[event]
+pub struct RewardSplitUpdated {
+    pub node: Pubkey,
+    pub beneficiaries: Vec<Beneficiary>,
+    pub locked_until: i64,
+}
+
+/// Pending rewards paid out to the node owner or its beneficiaries
+#[event]
+pub struct RewardsClaimed {
+    pub node: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+}
+
+/// Pending rewards moved into the node's registry stake instead of paid out
+#[event]
+pub struct RewardsRestaked {
+    pub node: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+}
+
+/// Pause flags changed by the authority or guardian
+#[event]
+pub struct PauseUpdated {
+    pub signer: Pubkey,
+    pub paused: u8,
+}
@@ -0,0 +1,154 @@
+//! Deterministic per-period node reward from `RewardParams`.
+//!
+//! ```text
+//! uptime_bonus = base_reward * uptime_multiplier_bps / 10000 * uptime / 100
+//! data_reward  = data_transferred_delta * data_reward_rate / 1 GB
+//! user_reward  = users_served * user_reward_rate
+//! reward       = (base_reward + uptime_bonus + data_reward + user_reward)
+//!                * coverage_score_bps / 10000
+//! ```
+//!
+//! Intermediate products are taken in `u128` and every division rounds down.
+
+use anchor_lang::prelude::*;
+use crate::errors::*;
+use crate::state::*;
+
+/// Measurements a node is rewarded on for one period
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct RewardInputs {
+    /// Average uptime percentage (0-100) over the registry heartbeat history
+    pub uptime_percentage: u8,
+    /// Bytes transferred since the node's previous calculation
+    pub data_transferred_delta: u64,
+    /// Average users served over the registry heartbeat history
+    pub users_served: u32,
+    /// Share of proof-of-coverage challenges passed, in basis points
+    pub coverage_score_bps: u16,
+}
+
+pub fn calculate_reward(params: &RewardParams, inputs: &RewardInputs) -> Result<u64> {
+    require!(
+        inputs.uptime_percentage >= params.min_uptime,
+        RewardsError::NodeNotEligible
+    );
+
+    let base_reward = params.base_reward as u128;
+
+    let uptime_bonus = base_reward
+        .checked_mul(params.uptime_multiplier as u128)
+        .and_then(|bonus| bonus.checked_mul(inputs.uptime_percentage as u128))
+        .ok_or(RewardsError::MathOverflow)?
+        / (BPS_DENOMINATOR as u128 * 100);
+
+    let data_reward = (inputs.data_transferred_delta as u128)
+        .checked_mul(params.data_reward_rate as u128)
+        .ok_or(RewardsError::MathOverflow)?
+        / BYTES_PER_GB as u128;
+
+    let user_reward = (inputs.users_served as u128)
+        .checked_mul(params.user_reward_rate as u128)
+        .ok_or(RewardsError::MathOverflow)?;
+
+    let reward = base_reward
+        .checked_add(uptime_bonus)
+        .and_then(|reward| reward.checked_add(data_reward))
+        .and_then(|reward| reward.checked_add(user_reward))
+        .and_then(|reward| reward.checked_mul(inputs.coverage_score_bps as u128))
+        .ok_or(RewardsError::MathOverflow)?
+        / BPS_DENOMINATOR as u128;
+
+    u64::try_from(reward).map_err(|_| error!(RewardsError::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> RewardParams {
+        RewardParams {
+            base_reward: 1_000,
+            uptime_multiplier: 5_000,
+            data_reward_rate: 10,
+            user_reward_rate: 2,
+            min_uptime: 90,
+            period_duration: 86_400,
+            vesting_bps: 0,
+            vesting_duration: 0,
+            vesting_cliff: 0,
+        }
+    }
+
+    fn inputs() -> RewardInputs {
+        RewardInputs {
+            uptime_percentage: 100,
+            data_transferred_delta: 5 * BYTES_PER_GB,
+            users_served: 25,
+            coverage_score_bps: BPS_DENOMINATOR as u16,
+        }
+    }
+
+    #[test]
+    fn combines_components() {
+        // 1000 base + 500 uptime bonus + 50 data + 50 users
+        assert_eq!(calculate_reward(&params(), &inputs()).unwrap(), 1_600);
+
+        let half_coverage = RewardInputs { coverage_score_bps: 5_000, ..inputs() };
+        assert_eq!(calculate_reward(&params(), &half_coverage).unwrap(), 800);
+
+        let no_coverage = RewardInputs { coverage_score_bps: 0, ..inputs() };
+        assert_eq!(calculate_reward(&params(), &no_coverage).unwrap(), 0);
+    }
+
+    #[test]
+    fn rejects_uptime_below_minimum() {
+        let at_minimum = RewardInputs { uptime_percentage: 90, ..inputs() };
+        assert!(calculate_reward(&params(), &at_minimum).is_ok());
+
+        let below_minimum = RewardInputs { uptime_percentage: 89, ..inputs() };
+        assert_eq!(
+            calculate_reward(&params(), &below_minimum).unwrap_err(),
+            RewardsError::NodeNotEligible.into()
+        );
+    }
+
+    #[test]
+    fn rounds_each_division_down() {
+        let params = RewardParams {
+            base_reward: 3,
+            data_reward_rate: 1,
+            ..params()
+        };
+        let inputs = RewardInputs {
+            uptime_percentage: 99,
+            // Just under 1 GB earns nothing at 1 token per GB
+            data_transferred_delta: BYTES_PER_GB - 1,
+            users_served: 0,
+            coverage_score_bps: 3_333,
+        };
+
+        // Uptime bonus 3 * 5000 * 99 / 1_000_000 = 1.485 -> 1, then
+        // (3 + 1) * 3333 / 10000 = 1.3332 -> 1
+        assert_eq!(calculate_reward(&params, &inputs).unwrap(), 1);
+    }
+
+    #[test]
+    fn reports_overflow() {
+        // Intermediate products fit in u128, but the result exceeds u64
+        let params = RewardParams { base_reward: u64::MAX, ..params() };
+        assert_eq!(
+            calculate_reward(&params, &inputs()).unwrap_err(),
+            RewardsError::MathOverflow.into()
+        );
+
+        // A full-coverage u64::MAX reward with no bonus still fits
+        let params = RewardParams {
+            base_reward: u64::MAX,
+            uptime_multiplier: 0,
+            data_reward_rate: 0,
+            user_reward_rate: 0,
+            ..params
+        };
+        assert_eq!(calculate_reward(&params, &inputs()).unwrap(), u64::MAX);
+    }
+}
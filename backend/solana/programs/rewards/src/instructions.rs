@@ -1,7 +1,16 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
+use thepublic_node_registry::cpi::accounts::{AddStake, CreditRewards};
+use thepublic_node_registry::program::ThepublicNodeRegistry;
+use thepublic_node_view::NodeView;
+use crate::coverage::*;
+use crate::formula::*;
+use crate::pause::*;
 use crate::state::*;
 use crate::errors::*;
+use crate::events::*;
+use crate::split;
+use crate::vesting;
 
 #[derive(Accounts)]
 pub struct InitializeRewards<'info> {
@@ -35,12 +44,11 @@ pub struct InitializeRewards<'info> {
 }
 
 #[derive(Accounts)]
-pub struct DistributeRewards<'info> {
+pub struct FundVault<'info> {
     #[account(
         mut,
         seeds = [b"rewards_state"],
-        bump,
-        has_one = authority
+        bump
     )]
     pub rewards_state: Account<'info, RewardsState>,
     
@@ -51,6 +59,91 @@ pub struct DistributeRewards<'info> {
     )]
     pub reward_vault: Account<'info, TokenAccount>,
     
+    #[account(
+        mut,
+        token::mint = rewards_state.reward_mint,
+        token::authority = funder
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    
+    pub funder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(registration_nonce: u64)]
+pub struct InitNodeRewardAccount<'info> {
+    #[account(
+        seeds = [b"rewards_state"],
+        bump
+    )]
+    pub rewards_state: Account<'info, RewardsState>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = NodeRewardAccount::LEN,
+        seeds = [
+            b"node_rewards",
+            node_account.key().as_ref(),
+            registration_nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub node_reward_account: Account<'info, NodeRewardAccount>,
+    
+    /// CHECK: Validated as a registry node account by `NodeView::load`
+    pub node_account: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CalculateNodeReward<'info> {
+    #[account(
+        seeds = [b"rewards_state"],
+        bump
+    )]
+    pub rewards_state: Account<'info, RewardsState>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"node_rewards",
+            node_reward_account.node_account.as_ref(),
+            node_reward_account.registration_nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub node_reward_account: Account<'info, NodeRewardAccount>,
+    
+    /// CHECK: Validated as a registry node account by `NodeView::load`
+    #[account(address = node_reward_account.node_account @ RewardsError::NodeRewardAccountMismatch)]
+    pub node_account: UncheckedAccount<'info>,
+    
+    /// CHECK: Validated as the registry network state by `NodeView::load_network_state`
+    pub network_state: UncheckedAccount<'info>,
+    
+    /// CHECK: Validated as the node's registry heartbeat history by
+    /// `NodeView::load_performance_history`
+    pub performance_history: UncheckedAccount<'info>,
+    
+    /// CHECK: Validated as a proof-of-coverage node account by `load_coverage_node`
+    pub coverage_node: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct OpenDistribution<'info> {
+    #[account(
+        seeds = [b"rewards_state"],
+        bump,
+        has_one = authority
+    )]
+    pub rewards_state: Account<'info, RewardsState>,
+    
     #[account(
         init,
         payer = authority,
@@ -60,21 +153,23 @@ pub struct DistributeRewards<'info> {
     )]
     pub distribution: Account<'info, RewardDistribution>,
     
+    #[account(mut)]
     pub authority: Signer<'info>,
-    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct ClaimRewards<'info> {
+#[instruction(merkle_root: [u8; 32], total_amount: u64, total_nodes: u32)]
+pub struct DistributeRewards<'info> {
     #[account(
+        mut,
         seeds = [b"rewards_state"],
-        bump
+        bump,
+        has_one = authority
     )]
     pub rewards_state: Account<'info, RewardsState>,
     
     #[account(
-        mut,
         seeds = [b"reward_vault"],
         bump
     )]
@@ -82,25 +177,31 @@ pub struct ClaimRewards<'info> {
     
     #[account(
         mut,
-        seeds = [b"node_rewards", node_reward_account.node_account.as_ref()],
-        bump,
-        has_one = owner
+        seeds = [b"distribution", rewards_state.current_period.to_le_bytes().as_ref()],
+        bump
     )]
-    pub node_reward_account: Account<'info, NodeRewardAccount>,
+    pub distribution: Account<'info, RewardDistribution>,
     
     #[account(
-        mut,
-        token::mint = rewards_state.reward_mint,
-        token::authority = owner
+        init,
+        payer = authority,
+        space = RewardBatch::space(total_nodes),
+        seeds = [
+            b"reward_batch",
+            rewards_state.current_period.to_le_bytes().as_ref(),
+            distribution.batch_count.to_le_bytes().as_ref()
+        ],
+        bump
     )]
-    pub owner_token_account: Account<'info, TokenAccount>,
+    pub reward_batch: Account<'info, RewardBatch>,
     
-    pub owner: Signer<'info>,
-    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateRewardParams<'info> {
+pub struct FinalizeDistribution<'info> {
     #[account(
         mut,
         seeds = [b"rewards_state"],
@@ -109,119 +210,1111 @@ pub struct UpdateRewardParams<'info> {
     )]
     pub rewards_state: Account<'info, RewardsState>,
     
+    #[account(
+        seeds = [b"reward_vault"],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"distribution", rewards_state.current_period.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub distribution: Account<'info, RewardDistribution>,
+    
     pub authority: Signer<'info>,
 }
 
-pub fn initialize_rewards(
-    ctx: Context<InitializeRewards>,
-    reward_params: RewardParams,
-) -> Result<()> {
-    let rewards_state = &mut ctx.accounts.rewards_state;
+#[derive(Accounts)]
+#[instruction(period: u64, batch: u16)]
+pub struct ClaimWithProof<'info> {
+    #[account(
+        seeds = [b"rewards_state"],
+        bump
+    )]
+    pub rewards_state: Account<'info, RewardsState>,
     
-    rewards_state.authority = ctx.accounts.authority.key();
-    rewards_state.reward_mint = ctx.accounts.reward_mint.key();
-    rewards_state.reward_vault = ctx.accounts.reward_vault.key();
-    rewards_state.reward_params = reward_params;
-    rewards_state.total_distributed = 0;
-    rewards_state.current_period = 0;
+    #[account(
+        seeds = [b"distribution", period.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub distribution: Account<'info, RewardDistribution>,
     
-    msg!("Rewards program initialized");
-    Ok(())
+    #[account(
+        mut,
+        seeds = [b"reward_batch", period.to_le_bytes().as_ref(), batch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub reward_batch: Account<'info, RewardBatch>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"node_rewards",
+            node_reward_account.node_account.as_ref(),
+            node_reward_account.registration_nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub node_reward_account: Account<'info, NodeRewardAccount>,
+    
+    /// CHECK: Registry node account, validated by the registry during `credit_rewards`
+    #[account(
+        mut,
+        address = node_reward_account.node_account @ RewardsError::NodeRewardAccountMismatch
+    )]
+    pub node_account: UncheckedAccount<'info>,
+    
+    /// Receives the locked part of the claim; omitted when nothing vests
+    #[account(
+        init,
+        payer = payer,
+        space = VestingSchedule::LEN,
+        seeds = [
+            b"vesting",
+            node_reward_account.key().as_ref(),
+            node_reward_account.vesting_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vesting_schedule: Option<Account<'info, VestingSchedule>>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub node_registry_program: Program<'info, ThepublicNodeRegistry>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn distribute_rewards(
-    ctx: Context<DistributeRewards>,
-    node_accounts: Vec<Pubkey>,
-    reward_amounts: Vec<u64>,
-) -> Result<()> {
-    let rewards_state = &mut ctx.accounts.rewards_state;
-    let distribution = &mut ctx.accounts.distribution;
-    
-    require!(
-        node_accounts.len() == reward_amounts.len(),
-        RewardsError::MismatchedArrayLengths
-    );
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        mut,
+        seeds = [b"rewards_state"],
+        bump
+    )]
+    pub rewards_state: Account<'info, RewardsState>,
     
-    let total_amount: u64 = reward_amounts.iter().sum();
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
     
-    require!(
-        ctx.accounts.reward_vault.amount >= total_amount,
-        RewardsError::InsufficientVaultBalance
-    );
+    #[account(
+        mut,
+        seeds = [
+            b"node_rewards",
+            node_reward_account.node_account.as_ref(),
+            node_reward_account.registration_nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub node_reward_account: Account<'info, NodeRewardAccount>,
     
-    // Initialize distribution record
-    distribution.period = rewards_state.current_period;
-    distribution.total_nodes = node_accounts.len() as u32;
-    distribution.total_amount = total_amount;
-    distribution.distributed_at = Clock::get()?.unix_timestamp;
-    distribution.merkle_root = [0; 32]; // Placeholder for merkle root
+    /// CHECK: Registry node account the owner is read from, validated by
+    /// `NodeView::load` unless the node was deregistered
+    #[account(address = node_reward_account.node_account @ RewardsError::NodeRewardAccountMismatch)]
+    pub node_account: UncheckedAccount<'info>,
     
-    // Update rewards state
-    rewards_state.total_distributed = rewards_state.total_distributed
-        .checked_add(total_amount)
-        .ok_or(RewardsError::MathOverflow)?;
+    #[account(
+        mut,
+        has_one = node_reward_account
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
     
-    rewards_state.current_period = rewards_state.current_period
-        .checked_add(1)
-        .ok_or(RewardsError::MathOverflow)?;
+    /// Required once the node has a split; beneficiary token accounts follow
+    /// in remaining_accounts
+    #[account(
+        seeds = [b"reward_split", node_reward_account.key().as_ref()],
+        bump
+    )]
+    pub reward_split: Option<Account<'info, RewardSplit>>,
     
-    msg!("Distributed {} tokens to {} nodes for period {}", 
-         total_amount, node_accounts.len(), distribution.period);
+    /// Receives the release unless the node's rewards are split
+    #[account(
+        mut,
+        token::mint = rewards_state.reward_mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
     
-    Ok(())
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
-pub fn claim_rewards(
-    ctx: Context<ClaimRewards>,
-    amount: u64,
-) -> Result<()> {
-    let node_reward_account = &mut ctx.accounts.node_reward_account;
-    
-    require!(
-        node_reward_account.pending_rewards >= amount,
-        RewardsError::InsufficientPendingRewards
-    );
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"rewards_state"],
+        bump
+    )]
+    pub rewards_state: Account<'info, RewardsState>,
     
-    // Transfer tokens from vault to owner
-    let seeds = &[
-        b"rewards_state",
-        &[ctx.bumps.rewards_state]
-    ];
-    let signer_seeds = &[&seeds[..]];
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
     
-    token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.reward_vault.to_account_info(),
-                to: ctx.accounts.owner_token_account.to_account_info(),
-                authority: ctx.accounts.rewards_state.to_account_info(),
-            },
-            signer_seeds
-        ),
-        amount,
-    )?;
+    #[account(
+        mut,
+        seeds = [
+            b"node_rewards",
+            node_reward_account.node_account.as_ref(),
+            node_reward_account.registration_nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub node_reward_account: Account<'info, NodeRewardAccount>,
     
-    // Update node reward account
-    node_reward_account.pending_rewards = node_reward_account.pending_rewards
-        .checked_sub(amount)
-        .ok_or(RewardsError::MathOverflow)?;
+    /// CHECK: Registry node account the owner is read from, validated by
+    /// `NodeView::load` unless the node was deregistered
+    #[account(address = node_reward_account.node_account @ RewardsError::NodeRewardAccountMismatch)]
+    pub node_account: UncheckedAccount<'info>,
     
-    node_reward_account.total_claimed = node_reward_account.total_claimed
-        .checked_add(amount)
-        .ok_or(RewardsError::MathOverflow)?;
+    /// Required once the node has a split; beneficiary token accounts follow
+    /// in remaining_accounts
+    #[account(
+        seeds = [b"reward_split", node_reward_account.key().as_ref()],
+        bump
+    )]
+    pub reward_split: Option<Account<'info, RewardSplit>>,
     
-    msg!("Claimed {} tokens for node {}", amount, node_reward_account.node_account);
+    /// Receives the claim unless the node's rewards are split
+    #[account(
+        mut,
+        token::mint = rewards_state.reward_mint,
+        token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
     
-    Ok(())
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
-pub fn update_reward_params(
+#[derive(Accounts)]
+pub struct SetRewardSplit<'info> {
+    #[account(
+        mut,
+        seeds = [b"rewards_state"],
+        bump
+    )]
+    pub rewards_state: Account<'info, RewardsState>,
+    
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"node_rewards",
+            node_reward_account.node_account.as_ref(),
+            node_reward_account.registration_nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub node_reward_account: Account<'info, NodeRewardAccount>,
+    
+    /// CHECK: Registry node account the owner is read from, validated by
+    /// `NodeView::load` unless the node was deregistered
+    #[account(address = node_reward_account.node_account @ RewardsError::NodeRewardAccountMismatch)]
+    pub node_account: UncheckedAccount<'info>,
+    
+    /// Pending rewards are first paid out under the current split; its
+    /// beneficiary token accounts follow in remaining_accounts
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = RewardSplit::LEN,
+        seeds = [b"reward_split", node_reward_account.key().as_ref()],
+        bump
+    )]
+    pub reward_split: Account<'info, RewardSplit>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAndRestake<'info> {
+    #[account(
+        mut,
+        seeds = [b"rewards_state"],
+        bump
+    )]
+    pub rewards_state: Account<'info, RewardsState>,
+    
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"node_rewards",
+            node_reward_account.node_account.as_ref(),
+            node_reward_account.registration_nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub node_reward_account: Account<'info, NodeRewardAccount>,
+    
+    /// CHECK: Registry node account, validated by the registry during `add_stake`
+    #[account(
+        mut,
+        address = node_reward_account.node_account @ RewardsError::NodeRewardAccountMismatch
+    )]
+    pub node_account: UncheckedAccount<'info>,
+    
+    /// CHECK: Registry network state, validated by the registry during `add_stake`
+    pub network_state: UncheckedAccount<'info>,
+    
+    /// CHECK: Node stake vault, validated by the registry during `add_stake`
+    #[account(mut)]
+    pub stake_vault: UncheckedAccount<'info>,
+    
+    pub owner: Signer<'info>,
+    pub node_registry_program: Program<'info, ThepublicNodeRegistry>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"rewards_state"],
+        bump,
+        constraint = rewards_state.can_pause(&signer.key()) @ RewardsError::NotAuthorityOrGuardian
+    )]
+    pub rewards_state: Account<'info, RewardsState>,
+    
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRewardParams<'info> {
+    #[account(
+        mut,
+        seeds = [b"rewards_state"],
+        bump,
+        has_one = authority
+    )]
+    pub rewards_state: Account<'info, RewardsState>,
+    
+    pub authority: Signer<'info>,
+}
+
+pub fn initialize_rewards(
+    ctx: Context<InitializeRewards>,
+    reward_params: RewardParams,
+    emission_schedule: EmissionSchedule,
+) -> Result<()> {
+    let rewards_state = &mut ctx.accounts.rewards_state;
+    
+    require!(reward_params.is_valid(), RewardsError::InvalidRewardParams);
+    require!(emission_schedule.is_valid(), RewardsError::InvalidEmissionSchedule);
+    
+    rewards_state.authority = ctx.accounts.authority.key();
+    rewards_state.reward_mint = ctx.accounts.reward_mint.key();
+    rewards_state.reward_vault = ctx.accounts.reward_vault.key();
+    rewards_state.reward_params = reward_params;
+    rewards_state.total_distributed = 0;
+    rewards_state.current_period = 0;
+    rewards_state.period_start = Clock::get()?.unix_timestamp;
+    rewards_state.emission_schedule = emission_schedule;
+    rewards_state.current_epoch = 0;
+    rewards_state.epoch_emitted = 0;
+    rewards_state.total_funded = 0;
+    rewards_state.total_paid = 0;
+    rewards_state.guardian = ctx.accounts.authority.key();
+    rewards_state.paused = 0;
+    
+    msg!("Rewards program initialized");
+    Ok(())
+}
+
+pub fn fund_vault(ctx: Context<FundVault>, amount: u64) -> Result<()> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_PARAM_UPDATE)?;
+    
+    require!(amount > 0, RewardsError::InvalidRewardAmount);
+    
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.funder_token_account.to_account_info(),
+                to: ctx.accounts.reward_vault.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    
+    let rewards_state = &mut ctx.accounts.rewards_state;
+    rewards_state.total_funded = rewards_state.total_funded
+        .checked_add(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    msg!("Funded reward vault with {} tokens", amount);
+    Ok(())
+}
+
+pub fn init_node_reward_account(
+    ctx: Context<InitNodeRewardAccount>,
+    registration_nonce: u64,
+) -> Result<()> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_PARAM_UPDATE)?;
+    
+    let node = NodeView::load(&ctx.accounts.node_account)?;
+    let node_reward_account = &mut ctx.accounts.node_reward_account;
+    
+    require!(
+        node.node.registration_nonce == registration_nonce,
+        RewardsError::RegistrationMismatch
+    );
+    
+    node_reward_account.node_account = node.address;
+    node_reward_account.registration_nonce = registration_nonce;
+    node_reward_account.owner = node.node.owner;
+    node_reward_account.total_earned = 0;
+    node_reward_account.total_claimed = 0;
+    node_reward_account.last_calculated_period = 0;
+    node_reward_account.pending_rewards = 0;
+    node_reward_account.last_data_transferred = node.node.performance_metrics.data_transferred;
+    node_reward_account.next_calculation_period = 0;
+    node_reward_account.calculated_reward = 0;
+    node_reward_account.locked_rewards = 0;
+    node_reward_account.vesting_count = 0;
+    node_reward_account.split_configured = false;
+    
+    msg!("Reward account created for node {}", node.address);
+    Ok(())
+}
+
+pub fn calculate_node_reward(ctx: Context<CalculateNodeReward>) -> Result<u64> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_DISTRIBUTE)?;
+    
+    let node = NodeView::load(&ctx.accounts.node_account)?;
+    let network_state = NodeView::load_network_state(&ctx.accounts.network_state)?;
+    let performance = node.load_performance_history(&ctx.accounts.performance_history)?.summary();
+    let coverage_node = load_coverage_node(&ctx.accounts.coverage_node)?;
+    let node_reward_account = &mut ctx.accounts.node_reward_account;
+    let rewards_state = &ctx.accounts.rewards_state;
+    let period = rewards_state.current_period;
+    
+    // Suspended, inactive or low-reputation nodes earn nothing, and a
+    // re-registered node earns into its new registration's account
+    node.require_eligible(&network_state)?;
+    node_reward_account.require_registration(node.node.registration_nonce)?;
+    
+    // Coverage accounts aren't PDAs of the node, so they're tied by owner and
+    // by the node's spatial cell; one site's score can't be reused elsewhere
+    require!(
+        coverage_node.owner == node.node.owner && coverage_node.hex_cell == node.node.hex_cell,
+        RewardsError::CoverageNodeMismatch
+    );
+    require!(
+        coverage_node.status == CoverageNodeStatus::Active,
+        RewardsError::CoverageNodeNotActive
+    );
+    
+    require!(
+        period >= node_reward_account.next_calculation_period,
+        RewardsError::RewardPeriodNotReady
+    );
+    
+    // Uptime and users are averaged over the heartbeat history so a single
+    // report can't set them; the registry's data counter is cumulative
+    let metrics = &node.node.performance_metrics;
+    let inputs = RewardInputs {
+        uptime_percentage: performance.avg_uptime_percentage,
+        data_transferred_delta: metrics.data_transferred
+            .saturating_sub(node_reward_account.last_data_transferred),
+        users_served: performance.avg_users_served,
+        coverage_score_bps: coverage_score_bps(&coverage_node),
+    };
+    let reward = calculate_reward(&rewards_state.reward_params, &inputs)?;
+    
+    node_reward_account.last_data_transferred = metrics.data_transferred;
+    node_reward_account.next_calculation_period = period
+        .checked_add(1)
+        .ok_or(RewardsError::MathOverflow)?;
+    node_reward_account.calculated_reward = node_reward_account.calculated_reward
+        .checked_add(reward)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    emit!(NodeRewardCalculated {
+        node: node.address,
+        period,
+        inputs,
+        reward,
+    });
+    
+    Ok(reward)
+}
+
+pub fn open_distribution(ctx: Context<OpenDistribution>) -> Result<()> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_DISTRIBUTE)?;
+    
+    let rewards_state = &ctx.accounts.rewards_state;
+    let distribution = &mut ctx.accounts.distribution;
+    let now = Clock::get()?.unix_timestamp;
+    
+    let period_end = rewards_state.period_start
+        .checked_add(rewards_state.reward_params.period_seconds()?)
+        .ok_or(RewardsError::MathOverflow)?;
+    require!(now >= period_end, RewardsError::RewardPeriodNotReady);
+    
+    distribution.period = rewards_state.current_period;
+    distribution.total_nodes = 0;
+    distribution.total_amount = 0;
+    distribution.opened_at = now;
+    distribution.distributed_at = 0;
+    distribution.batch_count = 0;
+    distribution.finalized = false;
+    
+    msg!("Opened distribution for period {}", distribution.period);
+    Ok(())
+}
+
+pub fn distribute_rewards(
+    ctx: Context<DistributeRewards>,
+    merkle_root: [u8; 32],
+    total_amount: u64,
+    total_nodes: u32,
+) -> Result<()> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_DISTRIBUTE)?;
+    
+    let rewards_state = &mut ctx.accounts.rewards_state;
+    let distribution = &mut ctx.accounts.distribution;
+    let reward_batch = &mut ctx.accounts.reward_batch;
+    
+    require!(!distribution.finalized, RewardsError::DistributionAlreadyCompleted);
+    require!(total_nodes <= MAX_BATCH_NODES, RewardsError::DistributionTooLarge);
+    
+    // Charge the batch against the epoch's budget
+    let schedule = rewards_state.emission_schedule.clone();
+    let epoch = schedule.epoch_of(distribution.period);
+    if epoch != rewards_state.current_epoch {
+        rewards_state.current_epoch = epoch;
+        rewards_state.epoch_emitted = 0;
+    }
+    rewards_state.epoch_emitted = rewards_state.epoch_emitted
+        .checked_add(total_amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    require!(
+        rewards_state.epoch_emitted <= schedule.epoch_cap(epoch),
+        RewardsError::EpochEmissionExceeded
+    );
+    
+    distribution.total_amount = distribution.total_amount
+        .checked_add(total_amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    // Finalized distributions are already in `total_distributed`
+    let total_emission = rewards_state.total_distributed
+        .checked_add(distribution.total_amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    require!(
+        total_emission <= schedule.max_total_emission,
+        RewardsError::TotalEmissionExceeded
+    );
+    distribution.total_nodes = distribution.total_nodes
+        .checked_add(total_nodes)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    require!(
+        rewards_state.vault_covers(ctx.accounts.reward_vault.amount, distribution.total_amount),
+        RewardsError::InsufficientVaultBalance
+    );
+    
+    reward_batch.period = distribution.period;
+    reward_batch.batch = distribution.batch_count;
+    reward_batch.merkle_root = merkle_root;
+    reward_batch.total_nodes = total_nodes;
+    reward_batch.total_amount = total_amount;
+    reward_batch.claimed_amount = 0;
+    reward_batch.bits = vec![0; RewardBatch::bytes_for(total_nodes)];
+    
+    distribution.batch_count = distribution.batch_count
+        .checked_add(1)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    msg!("Appended batch {} of {} tokens to {} nodes for period {}",
+         reward_batch.batch, total_amount, total_nodes, distribution.period);
+    
+    Ok(())
+}
+
+pub fn finalize_distribution(ctx: Context<FinalizeDistribution>) -> Result<()> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_DISTRIBUTE)?;
+    
+    let rewards_state = &mut ctx.accounts.rewards_state;
+    let distribution = &mut ctx.accounts.distribution;
+    
+    require!(!distribution.finalized, RewardsError::DistributionAlreadyCompleted);
+    require!(
+        rewards_state.vault_covers(ctx.accounts.reward_vault.amount, distribution.total_amount),
+        RewardsError::InsufficientVaultBalance
+    );
+    
+    distribution.finalized = true;
+    distribution.distributed_at = Clock::get()?.unix_timestamp;
+    
+    // Update rewards state
+    rewards_state.total_distributed = rewards_state.total_distributed
+        .checked_add(distribution.total_amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    rewards_state.current_period = rewards_state.current_period
+        .checked_add(1)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    // Periods stay on schedule even when a distribution finalizes late
+    rewards_state.period_start = rewards_state.period_start
+        .checked_add(rewards_state.reward_params.period_seconds()?)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    msg!("Distributed {} tokens to {} nodes for period {}", 
+         distribution.total_amount, distribution.total_nodes, distribution.period);
+    
+    Ok(())
+}
+
+pub fn claim_with_proof(
+    ctx: Context<ClaimWithProof>,
+    period: u64,
+    _batch: u16,
+    index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_CLAIM)?;
+    
+    let reward_batch = &mut ctx.accounts.reward_batch;
+    let node_reward_account = &mut ctx.accounts.node_reward_account;
+    
+    // Batches can't be claimed until the distribution's totals are settled
+    require!(ctx.accounts.distribution.finalized, RewardsError::RewardPeriodNotReady);
+    require!(index < reward_batch.total_nodes, RewardsError::InvalidClaimIndex);
+    require!(!reward_batch.is_claimed(index), RewardsError::AlreadyClaimed);
+    
+    let leaf = thepublic_reward_merkle::leaf_hash(
+        period,
+        index,
+        &node_reward_account.node_account,
+        amount,
+    );
+    require!(
+        thepublic_reward_merkle::verify_proof(&reward_batch.merkle_root, leaf, index, &proof),
+        RewardsError::InvalidMerkleProof
+    );
+    
+    // The tree can't pay a node more than the on-chain formula computed for it
+    node_reward_account.calculated_reward = node_reward_account.calculated_reward
+        .checked_sub(amount)
+        .ok_or(RewardsError::ClaimExceedsCalculatedReward)?;
+    
+    reward_batch.claimed_amount = reward_batch.claimed_amount
+        .checked_add(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    require!(
+        reward_batch.claimed_amount <= reward_batch.total_amount,
+        RewardsError::ClaimExceedsDistribution
+    );
+    reward_batch.set_claimed(index);
+    
+    // Lock the vesting share, the rest is claimable right away
+    let node_reward_key = node_reward_account.key();
+    vesting::credit(
+        node_reward_account,
+        node_reward_key,
+        ctx.accounts.vesting_schedule.as_deref_mut(),
+        &ctx.accounts.rewards_state.reward_params,
+        amount,
+        Clock::get()?.unix_timestamp,
+    )?;
+    node_reward_account.last_calculated_period = period;
+    
+    // Keep the registry's lifetime totals in step
+    let seeds = &[
+        b"rewards_state".as_ref(),
+        &[ctx.bumps.rewards_state],
+    ];
+    let signer_seeds = &[&seeds[..]];
+    
+    thepublic_node_registry::cpi::credit_rewards(
+        CpiContext::new_with_signer(
+            ctx.accounts.node_registry_program.to_account_info(),
+            CreditRewards {
+                node_account: ctx.accounts.node_account.to_account_info(),
+                rewards_authority: ctx.accounts.rewards_state.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+    
+    msg!("Credited {} tokens to node {} for period {}",
+         amount, node_reward_account.node_account, period);
+    
+    Ok(())
+}
+
+pub fn claim_rewards<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_CLAIM)?;
+    require_node_owner(
+        &mut ctx.accounts.node_reward_account,
+        &ctx.accounts.node_account,
+        &ctx.accounts.owner.key(),
+    )?;
+    
+    require!(
+        ctx.accounts.node_reward_account.pending_rewards >= amount,
+        RewardsError::InsufficientPendingRewards
+    );
+    
+    let recipients = payout_recipients(
+        &ctx.accounts.node_reward_account,
+        ctx.accounts.reward_split.as_deref(),
+        &ctx.accounts.rewards_state.reward_mint,
+        ctx.accounts.owner_token_account.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+    )?;
+    
+    // Transfer tokens from vault to owner or beneficiaries
+    let seeds = &[
+        b"rewards_state".as_ref(),
+        &[ctx.bumps.rewards_state],
+    ];
+    let signer_seeds = &[&seeds[..]];
+    
+    for (recipient, share) in recipients {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.reward_vault.to_account_info(),
+                    to: recipient,
+                    authority: ctx.accounts.rewards_state.to_account_info(),
+                },
+                signer_seeds
+            ),
+            share,
+        )?;
+    }
+    
+    // Update node reward account
+    let node_reward_account = &mut ctx.accounts.node_reward_account;
+    node_reward_account.pending_rewards = node_reward_account.pending_rewards
+        .checked_sub(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    node_reward_account.total_claimed = node_reward_account.total_claimed
+        .checked_add(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    let rewards_state = &mut ctx.accounts.rewards_state;
+    rewards_state.total_paid = rewards_state.total_paid
+        .checked_add(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    emit!(RewardsClaimed {
+        node: node_reward_account.node_account,
+        owner: ctx.accounts.owner.key(),
+        amount,
+        total_claimed: node_reward_account.total_claimed,
+    });
+    
+    msg!("Claimed {} tokens for node {}", amount, node_reward_account.node_account);
+    
+    Ok(())
+}
+
+pub fn claim_and_restake(
+    ctx: Context<ClaimAndRestake>,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_CLAIM)?;
+    require_node_owner(
+        &mut ctx.accounts.node_reward_account,
+        &ctx.accounts.node_account,
+        &ctx.accounts.owner.key(),
+    )?;
+    
+    require!(amount > 0, RewardsError::InvalidRewardAmount);
+    require!(
+        ctx.accounts.node_reward_account.pending_rewards >= amount,
+        RewardsError::InsufficientPendingRewards
+    );
+    // Restaking would bypass the beneficiaries
+    require!(
+        !ctx.accounts.node_reward_account.split_configured,
+        RewardsError::RewardSplitActive
+    );
+    // Only into the registration that earned the rewards
+    let node = NodeView::load(&ctx.accounts.node_account)?;
+    ctx.accounts.node_reward_account.require_registration(node.node.registration_nonce)?;
+    
+    // The registry deposits from the vault on the rewards state's signature
+    // and rejects it unless the reward mint is also the stake mint
+    let seeds = &[
+        b"rewards_state".as_ref(),
+        &[ctx.bumps.rewards_state],
+    ];
+    let signer_seeds = &[&seeds[..]];
+    
+    thepublic_node_registry::cpi::add_stake(
+        CpiContext::new_with_signer(
+            ctx.accounts.node_registry_program.to_account_info(),
+            AddStake {
+                node_account: ctx.accounts.node_account.to_account_info(),
+                network_state: ctx.accounts.network_state.to_account_info(),
+                stake_vault: ctx.accounts.stake_vault.to_account_info(),
+                source_token_account: ctx.accounts.reward_vault.to_account_info(),
+                depositor: ctx.accounts.rewards_state.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+    
+    let node_reward_account = &mut ctx.accounts.node_reward_account;
+    node_reward_account.pending_rewards = node_reward_account.pending_rewards
+        .checked_sub(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    node_reward_account.total_claimed = node_reward_account.total_claimed
+        .checked_add(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    let rewards_state = &mut ctx.accounts.rewards_state;
+    rewards_state.total_paid = rewards_state.total_paid
+        .checked_add(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    emit!(RewardsRestaked {
+        node: node_reward_account.node_account,
+        owner: ctx.accounts.owner.key(),
+        amount,
+        total_claimed: node_reward_account.total_claimed,
+    });
+    
+    msg!("Restaked {} tokens for node {}", amount, node_reward_account.node_account);
+    
+    Ok(())
+}
+
+pub fn claim_vested<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClaimVested<'info>>,
+) -> Result<()> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_CLAIM)?;
+    require_node_owner(
+        &mut ctx.accounts.node_reward_account,
+        &ctx.accounts.node_account,
+        &ctx.accounts.owner.key(),
+    )?;
+    
+    let amount = ctx.accounts.vesting_schedule.releasable(Clock::get()?.unix_timestamp);
+    require!(amount > 0, RewardsError::NothingVested);
+    
+    let recipients = payout_recipients(
+        &ctx.accounts.node_reward_account,
+        ctx.accounts.reward_split.as_deref(),
+        &ctx.accounts.rewards_state.reward_mint,
+        ctx.accounts.owner_token_account.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+    )?;
+    
+    let seeds = &[
+        b"rewards_state".as_ref(),
+        &[ctx.bumps.rewards_state],
+    ];
+    let signer_seeds = &[&seeds[..]];
+    
+    for (recipient, share) in recipients {
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.reward_vault.to_account_info(),
+                    to: recipient,
+                    authority: ctx.accounts.rewards_state.to_account_info(),
+                },
+                signer_seeds
+            ),
+            share,
+        )?;
+    }
+    
+    let vesting_schedule = &mut ctx.accounts.vesting_schedule;
+    let node_reward_account = &mut ctx.accounts.node_reward_account;
+    
+    vesting_schedule.released_amount = vesting_schedule.released_amount
+        .checked_add(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    node_reward_account.locked_rewards = node_reward_account.locked_rewards
+        .checked_sub(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    node_reward_account.total_claimed = node_reward_account.total_claimed
+        .checked_add(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    let rewards_state = &mut ctx.accounts.rewards_state;
+    rewards_state.total_paid = rewards_state.total_paid
+        .checked_add(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    msg!("Released {} vested tokens for node {}", amount, node_reward_account.node_account);
+    
+    // Return the rent of finished schedules to the owner
+    if vesting_schedule.is_fully_released() {
+        vesting_schedule.close(ctx.accounts.owner.to_account_info())?;
+    }
+    
+    Ok(())
+}
+
+pub fn set_reward_split<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetRewardSplit<'info>>,
+    beneficiaries: Vec<Beneficiary>,
+) -> Result<()> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_PARAM_UPDATE)?;
+    require_node_owner(
+        &mut ctx.accounts.node_reward_account,
+        &ctx.accounts.node_account,
+        &ctx.accounts.owner.key(),
+    )?;
+    
+    let now = Clock::get()?.unix_timestamp;
+    let split_configured = ctx.accounts.node_reward_account.split_configured;
+    let removing = beneficiaries.is_empty();
+    
+    // An empty list removes the current split
+    require!(
+        if removing { split_configured } else { split::is_valid_split(&beneficiaries) },
+        RewardsError::InvalidRewardSplit
+    );
+    require!(now >= ctx.accounts.reward_split.locked_until, RewardsError::RewardSplitLocked);
+    
+    // Rewards credited under the current split are paid out under it, so a
+    // claim credited just before the change still reaches its beneficiaries
+    let pending = ctx.accounts.node_reward_account.pending_rewards;
+    if split_configured && pending > 0 {
+        ctx.accounts.rewards_state.require_unpaused(PAUSE_CLAIM)?;
+        
+        let recipients = split_recipients(
+            &ctx.accounts.reward_split,
+            &ctx.accounts.rewards_state.reward_mint,
+            ctx.remaining_accounts,
+            pending,
+        )?;
+        
+        let seeds = &[
+            b"rewards_state".as_ref(),
+            &[ctx.bumps.rewards_state],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        
+        for (recipient, share) in recipients {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.reward_vault.to_account_info(),
+                        to: recipient,
+                        authority: ctx.accounts.rewards_state.to_account_info(),
+                    },
+                    signer_seeds
+                ),
+                share,
+            )?;
+        }
+        
+        let node_reward_account = &mut ctx.accounts.node_reward_account;
+        node_reward_account.pending_rewards = 0;
+        node_reward_account.total_claimed = node_reward_account.total_claimed
+            .checked_add(pending)
+            .ok_or(RewardsError::MathOverflow)?;
+        
+        let rewards_state = &mut ctx.accounts.rewards_state;
+        rewards_state.total_paid = rewards_state.total_paid
+            .checked_add(pending)
+            .ok_or(RewardsError::MathOverflow)?;
+        
+        emit!(RewardsClaimed {
+            node: node_reward_account.node_account,
+            owner: ctx.accounts.owner.key(),
+            amount: pending,
+            total_claimed: node_reward_account.total_claimed,
+        });
+    }
+    
+    let reward_split = &mut ctx.accounts.reward_split;
+    let node_reward_account = &mut ctx.accounts.node_reward_account;
+    
+    if removing {
+        node_reward_account.split_configured = false;
+        
+        emit!(RewardSplitUpdated {
+            node: node_reward_account.node_account,
+            beneficiaries,
+            locked_until: 0,
+        });
+        
+        msg!("Reward split removed for node {}", node_reward_account.node_account);
+        return reward_split.close(ctx.accounts.owner.to_account_info());
+    }
+    
+    // Shares stay fixed for at least one reward period
+    let locked_until = now
+        .checked_add(ctx.accounts.rewards_state.reward_params.period_seconds()?)
+        .ok_or(RewardsError::MathOverflow)?;
+    
+    reward_split.node_reward_account = node_reward_account.key();
+    reward_split.beneficiaries = beneficiaries.clone();
+    reward_split.locked_until = locked_until;
+    node_reward_account.split_configured = true;
+    
+    emit!(RewardSplitUpdated {
+        node: node_reward_account.node_account,
+        beneficiaries,
+        locked_until,
+    });
+    
+    msg!("Reward split updated for node {}", node_reward_account.node_account);
+    Ok(())
+}
+
+/// Refreshes the stored owner from the registry and requires `signer` to be
+/// it; see `ownership`
+fn require_node_owner(
+    node_reward_account: &mut NodeRewardAccount,
+    node_account: &AccountInfo,
+    signer: &Pubkey,
+) -> Result<()> {
+    if !node_account.data_is_empty() {
+        let node = NodeView::load(node_account)?.node;
+        node_reward_account.refresh_owner(node.registration_nonce, node.owner);
+    }
+    
+    node_reward_account.require_owner(signer)
+}
+
+/// Token accounts a claim of `amount` is paid to, with each one's part
+fn payout_recipients<'info>(
+    node_reward_account: &NodeRewardAccount,
+    reward_split: Option<&RewardSplit>,
+    reward_mint: &Pubkey,
+    owner_token_account: AccountInfo<'info>,
+    beneficiary_accounts: &'info [AccountInfo<'info>],
+    amount: u64,
+) -> Result<Vec<(AccountInfo<'info>, u64)>> {
+    if !node_reward_account.split_configured {
+        return Ok(vec![(owner_token_account, amount)]);
+    }
+    
+    let reward_split = reward_split.ok_or(RewardsError::RewardSplitRequired)?;
+    split_recipients(reward_split, reward_mint, beneficiary_accounts, amount)
+}
+
+/// Beneficiary token accounts of `reward_split`, checked against its wallets,
+/// with each one's part of `amount`
+fn split_recipients<'info>(
+    reward_split: &RewardSplit,
+    reward_mint: &Pubkey,
+    beneficiary_accounts: &'info [AccountInfo<'info>],
+    amount: u64,
+) -> Result<Vec<(AccountInfo<'info>, u64)>> {
+    require!(
+        beneficiary_accounts.len() == reward_split.beneficiaries.len(),
+        RewardsError::BeneficiaryAccountMismatch
+    );
+    
+    reward_split.beneficiaries.iter()
+        .zip(reward_split.shares(amount))
+        .zip(beneficiary_accounts)
+        .map(|((beneficiary, share), info)| {
+            let token_account: Account<TokenAccount> = Account::try_from(info)?;
+            require!(
+                token_account.mint == *reward_mint && token_account.owner == beneficiary.wallet,
+                RewardsError::BeneficiaryAccountMismatch
+            );
+            Ok((info.clone(), share))
+        })
+        .collect()
+}
+
+pub fn set_paused(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+    let rewards_state = &mut ctx.accounts.rewards_state;
+    
+    require!(is_valid_pause(paused), RewardsError::InvalidPauseFlags);
+    rewards_state.paused = paused;
+    
+    emit!(PauseUpdated {
+        signer: ctx.accounts.signer.key(),
+        paused,
+    });
+    
+    msg!("Pause flags set to {:#05b}", paused);
+    Ok(())
+}
+
+pub fn set_guardian(ctx: Context<UpdateRewardParams>, guardian: Pubkey) -> Result<()> {
+    let rewards_state = &mut ctx.accounts.rewards_state;
+    
+    rewards_state.guardian = guardian;
+    
+    msg!("Guardian set to {}", guardian);
+    Ok(())
+}
+
+pub fn update_emission_schedule(
+    ctx: Context<UpdateRewardParams>,
+    emission_schedule: EmissionSchedule,
+) -> Result<()> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_PARAM_UPDATE)?;
+    
+    let rewards_state = &mut ctx.accounts.rewards_state;
+    
+    require!(emission_schedule.is_valid(), RewardsError::InvalidEmissionSchedule);
+    rewards_state.emission_schedule = emission_schedule;
+    
+    msg!("Emission schedule updated");
+    Ok(())
+}
+
+pub fn update_reward_params(
     ctx: Context<UpdateRewardParams>,
     new_params: RewardParams,
 ) -> Result<()> {
+    ctx.accounts.rewards_state.require_unpaused(PAUSE_PARAM_UPDATE)?;
+    
     let rewards_state = &mut ctx.accounts.rewards_state;
     
+    require!(new_params.is_valid(), RewardsError::InvalidRewardParams);
     rewards_state.reward_params = new_params;
     
     msg!("Reward parameters updated");
@@ -1,12 +1,21 @@
 use anchor_lang::prelude::*;
 
-declare_id!("22222222222222222222222222222223"); // Placeholder - replace with actual program ID
+declare_id!("RewardsProgram11111111111111111111111111111"); // Placeholder - replace with actual program ID
 
+pub mod coverage;
+pub mod emission;
+pub mod formula;
 pub mod instructions;
+pub mod ownership;
+pub mod pause;
+pub mod split;
 pub mod state;
 pub mod errors;
+pub mod events;
+pub mod vesting;
 
 use instructions::*;
+use state::*;
 
 #[program]
 pub mod thepublic_rewards {
@@ -16,27 +25,117 @@ pub mod thepublic_rewards {
     pub fn initialize_rewards(
         ctx: Context<InitializeRewards>,
         reward_params: RewardParams,
+        emission_schedule: EmissionSchedule,
     ) -> Result<()> {
-        instructions::initialize_rewards(ctx, reward_params)
+        instructions::initialize_rewards(ctx, reward_params, emission_schedule)
     }
 
-    /// Calculate and distribute rewards for a period
+    /// Deposit reward tokens into the vault (permissionless)
+    pub fn fund_vault(ctx: Context<FundVault>, amount: u64) -> Result<()> {
+        instructions::fund_vault(ctx, amount)
+    }
+
+    /// Create the reward account of a node's current registration (permissionless)
+    pub fn init_node_reward_account(
+        ctx: Context<InitNodeRewardAccount>,
+        registration_nonce: u64,
+    ) -> Result<()> {
+        instructions::init_node_reward_account(ctx, registration_nonce)
+    }
+
+    /// Compute a node's reward for the current period from its registry
+    /// heartbeat history and coverage challenge record (permissionless);
+    /// proof claims are capped by the computed rewards
+    pub fn calculate_node_reward(ctx: Context<CalculateNodeReward>) -> Result<u64> {
+        instructions::calculate_node_reward(ctx)
+    }
+
+    /// Open the current period's distribution once `period_duration` has
+    /// elapsed (admin only)
+    pub fn open_distribution(ctx: Context<OpenDistribution>) -> Result<()> {
+        instructions::open_distribution(ctx)
+    }
+
+    /// Append a batch to the open distribution as a merkle root over
+    /// `total_nodes` leaves (admin only)
     pub fn distribute_rewards(
         ctx: Context<DistributeRewards>,
-        node_accounts: Vec<Pubkey>,
-        reward_amounts: Vec<u64>,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+        total_nodes: u32,
     ) -> Result<()> {
-        instructions::distribute_rewards(ctx, node_accounts, reward_amounts)
+        instructions::distribute_rewards(ctx, merkle_root, total_amount, total_nodes)
+    }
+
+    /// Close the distribution to new batches, open it to claims and start
+    /// the next period (admin only)
+    pub fn finalize_distribution(ctx: Context<FinalizeDistribution>) -> Result<()> {
+        instructions::finalize_distribution(ctx)
+    }
+
+    /// Credit a node's allocation in a finalized distribution batch by
+    /// merkle proof (permissionless)
+    pub fn claim_with_proof(
+        ctx: Context<ClaimWithProof>,
+        period: u64,
+        batch: u16,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::claim_with_proof(ctx, period, batch, index, amount, proof)
     }
 
     /// Claim rewards for a specific node
-    pub fn claim_rewards(
-        ctx: Context<ClaimRewards>,
+    pub fn claim_rewards<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>,
         amount: u64,
     ) -> Result<()> {
         instructions::claim_rewards(ctx, amount)
     }
 
+    /// Set the beneficiaries a node's claims are split between, or remove the
+    /// split with an empty list
+    pub fn set_reward_split<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetRewardSplit<'info>>,
+        beneficiaries: Vec<Beneficiary>,
+    ) -> Result<()> {
+        instructions::set_reward_split(ctx, beneficiaries)
+    }
+
+    /// Claim rewards straight into the node's registry stake
+    pub fn claim_and_restake(
+        ctx: Context<ClaimAndRestake>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::claim_and_restake(ctx, amount)
+    }
+
+    /// Release the unlocked part of a vesting schedule to the node owner
+    pub fn claim_vested<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimVested<'info>>,
+    ) -> Result<()> {
+        instructions::claim_vested(ctx)
+    }
+
+    /// Set the paused instruction groups (authority or guardian)
+    pub fn set_paused(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+        instructions::set_paused(ctx, paused)
+    }
+
+    /// Replace the guardian key (admin only)
+    pub fn set_guardian(ctx: Context<UpdateRewardParams>, guardian: Pubkey) -> Result<()> {
+        instructions::set_guardian(ctx, guardian)
+    }
+
+    /// Replace the emission schedule (admin only)
+    pub fn update_emission_schedule(
+        ctx: Context<UpdateRewardParams>,
+        emission_schedule: EmissionSchedule,
+    ) -> Result<()> {
+        instructions::update_emission_schedule(ctx, emission_schedule)
+    }
+
     /// Update reward parameters (admin only)
     pub fn update_reward_params(
         ctx: Context<UpdateRewardParams>,
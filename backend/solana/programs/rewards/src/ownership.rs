@@ -0,0 +1,93 @@
+//! Who may claim a node's rewards.
+//!
+//! A `NodeRewardAccount` belongs to one registration of a node, identified by
+//! the registry's `registration_nonce`. While that registration lives the
+//! owner follows the registry, so ownership transfers carry over. Once the
+//! node is deregistered, or its `node_id` registered again, the account keeps
+//! the registration's last owner: a new registrant gets a reward account of
+//! their own and cannot claim the previous operator's rewards.
+
+use anchor_lang::prelude::*;
+use crate::errors::*;
+use crate::state::*;
+
+impl NodeRewardAccount {
+    /// Takes `owner` from the live registry node if it is still this
+    /// account's registration
+    pub fn refresh_owner(&mut self, registration_nonce: u64, owner: Pubkey) {
+        if registration_nonce == self.registration_nonce {
+            self.owner = owner;
+        }
+    }
+
+    pub fn require_owner(&self, signer: &Pubkey) -> Result<()> {
+        require_keys_eq!(self.owner, *signer, RewardsError::NotNodeOwner);
+        Ok(())
+    }
+
+    /// Requires the live registry node to be this account's registration
+    pub fn require_registration(&self, registration_nonce: u64) -> Result<()> {
+        require!(
+            registration_nonce == self.registration_nonce,
+            RewardsError::RegistrationMismatch
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reward_account(registration_nonce: u64, owner: Pubkey) -> NodeRewardAccount {
+        NodeRewardAccount {
+            node_account: Pubkey::new_unique(),
+            registration_nonce,
+            owner,
+            pending_rewards: 600,
+            locked_rewards: 400,
+            ..NodeRewardAccount::default()
+        }
+    }
+
+    #[test]
+    fn owner_follows_transfers_within_registration() {
+        let (original, buyer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut account = reward_account(1, original);
+
+        account.refresh_owner(1, buyer);
+
+        assert!(account.require_owner(&buyer).is_ok());
+        assert_eq!(
+            account.require_owner(&original).err(),
+            Some(error!(RewardsError::NotNodeOwner))
+        );
+    }
+
+    #[test]
+    fn reregistered_node_cannot_claim_previous_rewards() {
+        let (operator, registrant) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut account = reward_account(1, operator);
+
+        // Deregistered: the registry account is gone and the owner is kept
+        assert!(account.require_owner(&operator).is_ok());
+
+        // The same `node_id` registered again by someone else
+        account.refresh_owner(2, registrant);
+
+        assert!(account.require_owner(&operator).is_ok());
+        assert_eq!(
+            account.require_owner(&registrant).err(),
+            Some(error!(RewardsError::NotNodeOwner))
+        );
+        assert_eq!(
+            account.require_registration(2).err(),
+            Some(error!(RewardsError::RegistrationMismatch))
+        );
+
+        // The new registration earns into an account of its own
+        let new_account = reward_account(2, registrant);
+        assert!(new_account.require_registration(2).is_ok());
+        assert!(new_account.require_owner(&registrant).is_ok());
+    }
+}
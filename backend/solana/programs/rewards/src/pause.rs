@@ -0,0 +1,415 @@
+//! Emergency stop for groups of instructions.
+//!
+//! `RewardsState::paused` holds one bit per group. The authority or the
+//! guardian sets them with `set_paused`:
+//!
+//! - `PAUSE_DISTRIBUTE`: `calculate_node_reward`, `open_distribution`,
+//!   `distribute_rewards`, `finalize_distribution`
+//! - `PAUSE_CLAIM`: `claim_with_proof`, `claim_rewards`, `claim_and_restake`,
+//!   `claim_vested`
+//! - `PAUSE_PARAM_UPDATE`: `update_reward_params`, `update_emission_schedule`,
+//!   `set_reward_split`, `fund_vault`, `init_node_reward_account`
+//!
+//! Only `set_paused` and `set_guardian` stay open, so a paused program can
+//! still be resumed and a compromised guardian replaced, along with
+//! `initialize_rewards`, which runs before there are flags to check. The
+//! payment channel program is separate and has no pause flags.
+
+use anchor_lang::prelude::*;
+use crate::errors::*;
+use crate::state::*;
+
+pub const PAUSE_DISTRIBUTE: u8 = 1 << 0;
+pub const PAUSE_CLAIM: u8 = 1 << 1;
+pub const PAUSE_PARAM_UPDATE: u8 = 1 << 2;
+pub const PAUSE_ALL: u8 = PAUSE_DISTRIBUTE | PAUSE_CLAIM | PAUSE_PARAM_UPDATE;
+
+/// Rejects `operation` if any of its bits are set in `paused`
+pub fn require_unpaused(paused: u8, operation: u8) -> Result<()> {
+    require!(paused & operation == 0, RewardsError::ProgramPaused);
+    Ok(())
+}
+
+pub fn is_valid_pause(paused: u8) -> bool {
+    paused & !PAUSE_ALL == 0
+}
+
+impl RewardsState {
+    pub fn require_unpaused(&self, operation: u8) -> Result<()> {
+        require_unpaused(self.paused, operation)
+    }
+
+    /// Whether `signer` may change the pause flags
+    pub fn can_pause(&self, signer: &Pubkey) -> bool {
+        *signer == self.authority || *signer == self.guardian
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::{self, *};
+    use anchor_lang::solana_program::bpf_loader_upgradeable;
+    use anchor_lang::solana_program::program_pack::Pack;
+    use anchor_lang::{system_program, Bumps};
+    use anchor_spl::token::{spl_token, TokenAccount};
+
+    fn leak<T>(value: T) -> &'static mut T {
+        Box::leak(Box::new(value))
+    }
+
+    /// A writable, non-signer account owned by `owner`
+    fn info(key: Pubkey, owner: Pubkey, data: Vec<u8>) -> AccountInfo<'static> {
+        AccountInfo::new(
+            leak(key),
+            false,
+            true,
+            leak(1_000_000_000),
+            Box::leak(data.into_boxed_slice()),
+            leak(owner),
+            false,
+            0,
+        )
+    }
+
+    fn program_account<T>(value: T) -> Account<'static, T>
+    where
+        T: AccountSerialize + AccountDeserialize + Owner + Clone,
+    {
+        let mut data = Vec::new();
+        value.try_serialize(&mut data).unwrap();
+
+        Account::try_from(&*leak(info(Pubkey::new_unique(), T::owner(), data))).unwrap()
+    }
+
+    fn rewards_state(paused: u8) -> Account<'static, RewardsState> {
+        program_account(RewardsState {
+            // `initialize_rewards` only accepts valid schedules
+            emission_schedule: EmissionSchedule { epoch_periods: 1, ..EmissionSchedule::default() },
+            paused,
+            ..RewardsState::default()
+        })
+    }
+
+    fn token_account() -> Account<'static, TokenAccount> {
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+
+        Account::try_from(&*leak(info(Pubkey::new_unique(), spl_token::ID, data))).unwrap()
+    }
+
+    fn unchecked() -> UncheckedAccount<'static> {
+        UncheckedAccount::try_from(&*leak(info(Pubkey::new_unique(), system_program::ID, Vec::new())))
+    }
+
+    fn signer() -> Signer<'static> {
+        let mut info = info(Pubkey::new_unique(), system_program::ID, Vec::new());
+        info.is_signer = true;
+
+        Signer::try_from(&*leak(info)).unwrap()
+    }
+
+    fn program<T: Id>() -> Program<'static, T> {
+        let mut info = info(T::id(), bpf_loader_upgradeable::ID, Vec::new());
+        info.executable = true;
+
+        Program::try_from(&*leak(info)).unwrap()
+    }
+
+    fn context<T>(accounts: &mut T) -> Context<'_, '_, 'static, 'static, T>
+    where
+        T: Bumps + Accounts<'static, T::Bumps>,
+        T::Bumps: Default,
+    {
+        Context::new(&crate::ID, accounts, &[], T::Bumps::default())
+    }
+
+    // Each runs a handler against a rewards state with `paused` set and
+    // otherwise empty accounts, so past the pause check it fails elsewhere
+
+    fn fund_vault(paused: u8) -> Result<()> {
+        let mut accounts = FundVault {
+            rewards_state: rewards_state(paused),
+            reward_vault: token_account(),
+            funder_token_account: token_account(),
+            funder: signer(),
+            token_program: program(),
+        };
+        instructions::fund_vault(context(&mut accounts), 0)
+    }
+
+    fn init_node_reward_account(paused: u8) -> Result<()> {
+        let mut accounts = InitNodeRewardAccount {
+            rewards_state: rewards_state(paused),
+            node_reward_account: program_account(NodeRewardAccount::default()),
+            node_account: unchecked(),
+            payer: signer(),
+            system_program: program(),
+        };
+        instructions::init_node_reward_account(context(&mut accounts), 0)
+    }
+
+    fn calculate_node_reward(paused: u8) -> Result<()> {
+        let mut accounts = CalculateNodeReward {
+            rewards_state: rewards_state(paused),
+            node_reward_account: program_account(NodeRewardAccount::default()),
+            node_account: unchecked(),
+            network_state: unchecked(),
+            performance_history: unchecked(),
+            coverage_node: unchecked(),
+        };
+        instructions::calculate_node_reward(context(&mut accounts)).map(drop)
+    }
+
+    fn open_distribution(paused: u8) -> Result<()> {
+        let mut accounts = OpenDistribution {
+            rewards_state: rewards_state(paused),
+            distribution: program_account(RewardDistribution::default()),
+            authority: signer(),
+            system_program: program(),
+        };
+        instructions::open_distribution(context(&mut accounts))
+    }
+
+    fn distribute_rewards(paused: u8) -> Result<()> {
+        let mut accounts = DistributeRewards {
+            rewards_state: rewards_state(paused),
+            reward_vault: token_account(),
+            distribution: program_account(RewardDistribution::default()),
+            reward_batch: program_account(RewardBatch::default()),
+            authority: signer(),
+            system_program: program(),
+        };
+        instructions::distribute_rewards(context(&mut accounts), [0; 32], 0, 0)
+    }
+
+    fn finalize_distribution(paused: u8) -> Result<()> {
+        let mut accounts = FinalizeDistribution {
+            rewards_state: rewards_state(paused),
+            reward_vault: token_account(),
+            distribution: program_account(RewardDistribution::default()),
+            authority: signer(),
+        };
+        instructions::finalize_distribution(context(&mut accounts))
+    }
+
+    fn claim_with_proof(paused: u8) -> Result<()> {
+        let mut accounts = ClaimWithProof {
+            rewards_state: rewards_state(paused),
+            distribution: program_account(RewardDistribution::default()),
+            reward_batch: program_account(RewardBatch::default()),
+            node_reward_account: program_account(NodeRewardAccount::default()),
+            node_account: unchecked(),
+            vesting_schedule: None,
+            payer: signer(),
+            node_registry_program: program(),
+            system_program: program(),
+        };
+        instructions::claim_with_proof(context(&mut accounts), 0, 0, 0, 0, Vec::new())
+    }
+
+    fn claim_rewards(paused: u8) -> Result<()> {
+        let mut accounts = ClaimRewards {
+            rewards_state: rewards_state(paused),
+            reward_vault: token_account(),
+            node_reward_account: program_account(NodeRewardAccount::default()),
+            node_account: unchecked(),
+            reward_split: None,
+            owner_token_account: token_account(),
+            owner: signer(),
+            token_program: program(),
+        };
+        instructions::claim_rewards(context(&mut accounts), 0)
+    }
+
+    fn claim_and_restake(paused: u8) -> Result<()> {
+        let mut accounts = ClaimAndRestake {
+            rewards_state: rewards_state(paused),
+            reward_vault: token_account(),
+            node_reward_account: program_account(NodeRewardAccount::default()),
+            node_account: unchecked(),
+            network_state: unchecked(),
+            stake_vault: unchecked(),
+            owner: signer(),
+            node_registry_program: program(),
+            token_program: program(),
+        };
+        instructions::claim_and_restake(context(&mut accounts), 0)
+    }
+
+    fn claim_vested(paused: u8) -> Result<()> {
+        let mut accounts = ClaimVested {
+            rewards_state: rewards_state(paused),
+            reward_vault: token_account(),
+            node_reward_account: program_account(NodeRewardAccount::default()),
+            node_account: unchecked(),
+            vesting_schedule: program_account(VestingSchedule::default()),
+            reward_split: None,
+            owner_token_account: token_account(),
+            owner: signer(),
+            token_program: program(),
+        };
+        instructions::claim_vested(context(&mut accounts))
+    }
+
+    fn set_reward_split(paused: u8) -> Result<()> {
+        let mut accounts = SetRewardSplit {
+            rewards_state: rewards_state(paused),
+            reward_vault: token_account(),
+            node_reward_account: program_account(NodeRewardAccount::default()),
+            node_account: unchecked(),
+            reward_split: program_account(RewardSplit::default()),
+            owner: signer(),
+            token_program: program(),
+            system_program: program(),
+        };
+        instructions::set_reward_split(context(&mut accounts), Vec::new())
+    }
+
+    fn update_params(paused: u8) -> UpdateRewardParams<'static> {
+        UpdateRewardParams {
+            rewards_state: rewards_state(paused),
+            authority: signer(),
+        }
+    }
+
+    fn update_emission_schedule(paused: u8) -> Result<()> {
+        let mut accounts = update_params(paused);
+        instructions::update_emission_schedule(context(&mut accounts), EmissionSchedule::default())
+    }
+
+    fn update_reward_params(paused: u8) -> Result<()> {
+        let mut accounts = update_params(paused);
+        instructions::update_reward_params(context(&mut accounts), RewardParams::default())
+    }
+
+    type Handler = fn(u8) -> Result<()>;
+
+    /// Handlers and the bits that must stop them, as documented above
+    const GATED: [(&str, Handler, u8); 13] = [
+        ("calculate_node_reward", calculate_node_reward, PAUSE_DISTRIBUTE),
+        ("open_distribution", open_distribution, PAUSE_DISTRIBUTE),
+        ("distribute_rewards", distribute_rewards, PAUSE_DISTRIBUTE),
+        ("finalize_distribution", finalize_distribution, PAUSE_DISTRIBUTE),
+        ("claim_with_proof", claim_with_proof, PAUSE_CLAIM),
+        ("claim_rewards", claim_rewards, PAUSE_CLAIM),
+        ("claim_and_restake", claim_and_restake, PAUSE_CLAIM),
+        ("claim_vested", claim_vested, PAUSE_CLAIM),
+        ("set_reward_split", set_reward_split, PAUSE_PARAM_UPDATE),
+        ("update_emission_schedule", update_emission_schedule, PAUSE_PARAM_UPDATE),
+        ("update_reward_params", update_reward_params, PAUSE_PARAM_UPDATE),
+        ("fund_vault", fund_vault, PAUSE_PARAM_UPDATE),
+        ("init_node_reward_account", init_node_reward_account, PAUSE_PARAM_UPDATE),
+    ];
+
+    #[test]
+    fn gated_handlers_reject_their_bit() {
+        for (name, handler, bit) in GATED {
+            assert_eq!(
+                handler(bit).unwrap_err(),
+                RewardsError::ProgramPaused.into(),
+                "{} ran while paused",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn gated_handlers_ignore_other_bits() {
+        for (name, handler, bit) in GATED {
+            assert_ne!(
+                handler(PAUSE_ALL & !bit).err(),
+                Some(RewardsError::ProgramPaused.into()),
+                "{} stopped by another bit",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn pause_controls_stay_open_while_paused() {
+        let mut accounts = update_params(PAUSE_ALL);
+        let guardian = Pubkey::new_unique();
+        instructions::set_guardian(context(&mut accounts), guardian).unwrap();
+        assert_eq!(accounts.rewards_state.guardian, guardian);
+
+        let mut accounts = SetPaused {
+            rewards_state: rewards_state(PAUSE_ALL),
+            signer: signer(),
+        };
+        instructions::set_paused(context(&mut accounts), 0).unwrap();
+        assert_eq!(accounts.rewards_state.paused, 0);
+    }
+
+    const OPERATIONS: [u8; 3] = [PAUSE_DISTRIBUTE, PAUSE_CLAIM, PAUSE_PARAM_UPDATE];
+
+    fn is_paused(paused: u8, operation: u8) -> bool {
+        let state = RewardsState { paused, ..RewardsState::default() };
+
+        match state.require_unpaused(operation) {
+            Ok(()) => false,
+            Err(error) => {
+                assert_eq!(error, RewardsError::ProgramPaused.into());
+                true
+            }
+        }
+    }
+
+    #[test]
+    fn nothing_paused_by_default() {
+        for operation in OPERATIONS {
+            assert!(!is_paused(0, operation));
+        }
+    }
+
+    #[test]
+    fn each_bit_pauses_only_its_operations() {
+        for paused in OPERATIONS {
+            for operation in OPERATIONS {
+                assert_eq!(is_paused(paused, operation), paused == operation);
+            }
+        }
+    }
+
+    #[test]
+    fn bits_combine() {
+        for operation in OPERATIONS {
+            assert!(is_paused(PAUSE_ALL, operation));
+        }
+
+        let paused = PAUSE_DISTRIBUTE | PAUSE_CLAIM;
+        assert!(is_paused(paused, PAUSE_DISTRIBUTE));
+        assert!(is_paused(paused, PAUSE_CLAIM));
+        assert!(!is_paused(paused, PAUSE_PARAM_UPDATE));
+    }
+
+    #[test]
+    fn rejects_unknown_bits() {
+        assert!(is_valid_pause(0));
+        assert!(is_valid_pause(PAUSE_ALL));
+        assert!(!is_valid_pause(1 << 3));
+        assert!(!is_valid_pause(u8::MAX));
+    }
+
+    #[test]
+    fn authority_and_guardian_can_pause() {
+        let authority = Pubkey::new_unique();
+        let guardian = Pubkey::new_unique();
+        let state = RewardsState {
+            authority,
+            guardian,
+            paused: PAUSE_CLAIM,
+            ..RewardsState::default()
+        };
+
+        assert!(state.can_pause(&authority));
+        assert!(state.can_pause(&guardian));
+        assert!(!state.can_pause(&Pubkey::new_unique()));
+        assert!(state.require_unpaused(PAUSE_DISTRIBUTE).is_ok());
+        assert!(state.require_unpaused(PAUSE_CLAIM).is_err());
+    }
+}
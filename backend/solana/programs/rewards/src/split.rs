@@ -0,0 +1,93 @@
+//! Splitting a node's claimed rewards between several beneficiaries.
+//!
+//! A node owner may configure a `RewardSplit` of up to `MAX_BENEFICIARIES`
+//! wallets whose basis-point shares sum to 10000, e.g. a host and the
+//! investors funding the hardware. Claims then pay every beneficiary its share
+//! directly, and the split cannot change again until a full reward period has
+//! passed.
+//!
+//! Changing or removing the split (an empty list) first pays any pending
+//! rewards out under the old one. Locked rewards are paid under whatever
+//! split is in place when they vest.
+
+use std::collections::BTreeSet;
+use crate::state::*;
+
+/// Whether `beneficiaries` is a usable split: 1 to `MAX_BENEFICIARIES`
+/// distinct wallets with non-zero shares summing to 10000
+pub fn is_valid_split(beneficiaries: &[Beneficiary]) -> bool {
+    let wallets: BTreeSet<_> = beneficiaries.iter().map(|b| b.wallet).collect();
+    let total: u64 = beneficiaries.iter().map(|b| b.share_bps as u64).sum();
+
+    (1..=MAX_BENEFICIARIES).contains(&beneficiaries.len())
+        && wallets.len() == beneficiaries.len()
+        && beneficiaries.iter().all(|b| b.share_bps > 0)
+        && total == BPS_DENOMINATOR
+}
+
+/// Each beneficiary's part of `amount`; rounding dust goes to the first one
+pub fn split_amount(amount: u64, beneficiaries: &[Beneficiary]) -> Vec<u64> {
+    let mut shares: Vec<u64> = beneficiaries
+        .iter()
+        .map(|b| (amount as u128 * b.share_bps as u128 / BPS_DENOMINATOR as u128) as u64)
+        .collect();
+
+    let dust = amount - shares.iter().sum::<u64>();
+    if let Some(first) = shares.first_mut() {
+        *first += dust;
+    }
+
+    shares
+}
+
+impl RewardSplit {
+    /// Each beneficiary's part of `amount`, in `beneficiaries` order
+    pub fn shares(&self, amount: u64) -> Vec<u64> {
+        split_amount(amount, &self.beneficiaries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+
+    fn beneficiaries(shares: &[u16]) -> Vec<Beneficiary> {
+        shares
+            .iter()
+            .map(|&share_bps| Beneficiary {
+                wallet: Pubkey::new_unique(),
+                share_bps,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn validates_splits() {
+        assert!(is_valid_split(&beneficiaries(&[10_000])));
+        assert!(is_valid_split(&beneficiaries(&[7_000, 2_000, 1_000])));
+        assert!(is_valid_split(&beneficiaries(&[1_250; MAX_BENEFICIARIES])));
+
+        assert!(!is_valid_split(&[]));
+        assert!(!is_valid_split(&beneficiaries(&[5_000, 4_999])));
+        assert!(!is_valid_split(&beneficiaries(&[5_000, 5_001])));
+        assert!(!is_valid_split(&beneficiaries(&[10_000, 0])));
+        assert!(!is_valid_split(&beneficiaries(&[1_000; MAX_BENEFICIARIES + 2])));
+
+        let mut duplicated = beneficiaries(&[5_000, 5_000]);
+        duplicated[1].wallet = duplicated[0].wallet;
+        assert!(!is_valid_split(&duplicated));
+    }
+
+    #[test]
+    fn splits_pay_out_the_full_amount() {
+        let split = beneficiaries(&[6_000, 3_000, 1_000]);
+
+        assert_eq!(split_amount(1_000, &split), [600, 300, 100]);
+        // Dust from rounding down goes to the first beneficiary
+        assert_eq!(split_amount(7, &split), [5, 2, 0]);
+        assert_eq!(split_amount(0, &split), [0, 0, 0]);
+        let total: u128 = split_amount(u64::MAX, &split).iter().map(|&share| share as u128).sum();
+        assert_eq!(total, u64::MAX as u128);
+    }
+}
@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
+use crate::errors::RewardsError;
 
 #[account]
+#[derive(Default)]
 pub struct RewardsState {
     /// Authority that can update reward parameters
     pub authority: Pubkey,
@@ -14,6 +16,23 @@ pub struct RewardsState {
     pub total_distributed: u64,
     /// Current reward period
     pub current_period: u64,
+    /// When the current period started; a distribution for it can open
+    /// `period_duration` seconds later
+    pub period_start: i64,
+    /// Limits on how much may be distributed, see `emission`
+    pub emission_schedule: EmissionSchedule,
+    /// Epoch `epoch_emitted` refers to
+    pub current_epoch: u64,
+    /// Amount appended to distributions during `current_epoch`
+    pub epoch_emitted: u64,
+    /// Total tokens deposited through `fund_vault`
+    pub total_funded: u64,
+    /// Total tokens paid out of the vault by claims and restakes
+    pub total_paid: u64,
+    /// Key that may pause instructions alongside the authority
+    pub guardian: Pubkey,
+    /// Paused instruction groups, see `pause`
+    pub paused: u8,
 }
 
 impl RewardsState {
@@ -23,10 +42,34 @@ impl RewardsState {
         32 + // reward_vault
         RewardParams::LEN +
         8 + // total_distributed
-        8; // current_period
+        8 + // current_period
+        8 + // period_start
+        EmissionSchedule::LEN +
+        8 + // current_epoch
+        8 + // epoch_emitted
+        8 + // total_funded
+        8 + // total_paid
+        32 + // guardian
+        1; // paused
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct EmissionSchedule {
+    /// Cap on the first epoch's emission
+    pub initial_epoch_emission: u64,
+    /// Reduction of the cap from one epoch to the next (basis points)
+    pub decay_bps: u16,
+    /// Reward periods per epoch
+    pub epoch_periods: u64,
+    /// Cap on everything ever distributed
+    pub max_total_emission: u64,
+}
+
+impl EmissionSchedule {
+    pub const LEN: usize = 8 + 2 + 8 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
 pub struct RewardParams {
     /// Base reward per period (in tokens)
     pub base_reward: u64,
@@ -40,17 +83,41 @@ pub struct RewardParams {
     pub min_uptime: u8,
     /// Reward period duration (seconds)
     pub period_duration: u64,
+    /// Share of each credited reward locked in a vesting schedule (basis points)
+    pub vesting_bps: u16,
+    /// Seconds over which locked rewards vest linearly
+    pub vesting_duration: i64,
+    /// Seconds after crediting before any locked reward unlocks
+    pub vesting_cliff: i64,
 }
 
 impl RewardParams {
-    pub const LEN: usize = 8 + 2 + 8 + 8 + 1 + 8;
+    pub const LEN: usize = 8 + 2 + 8 + 8 + 1 + 8 + 2 + 8 + 8;
+
+    /// A zero or out-of-range `period_duration` would defeat the period gate
+    pub fn is_valid(&self) -> bool {
+        (1..=i64::MAX as u64).contains(&self.period_duration)
+            && self.vesting_bps as u64 <= BPS_DENOMINATOR
+            && self.vesting_duration >= 0
+            && (0..=self.vesting_duration).contains(&self.vesting_cliff)
+    }
+
+    /// `period_duration` as a timestamp offset
+    pub fn period_seconds(&self) -> Result<i64> {
+        i64::try_from(self.period_duration).map_err(|_| error!(RewardsError::MathOverflow))
+    }
 }
 
 #[account]
+#[derive(Default)]
 pub struct NodeRewardAccount {
     /// Node account this reward account belongs to
     pub node_account: Pubkey,
-    /// Owner of the node
+    /// Registry `registration_nonce` of the registration it belongs to, see
+    /// `ownership`
+    pub registration_nonce: u64,
+    /// Owner of the node as last read from the registry; refreshed by every
+    /// owner-gated instruction
     pub owner: Pubkey,
     /// Total rewards earned
     pub total_earned: u64,
@@ -60,19 +127,101 @@ pub struct NodeRewardAccount {
     pub last_calculated_period: u64,
     /// Pending rewards
     pub pending_rewards: u64,
+    /// Registry data counter at the previous reward calculation
+    pub last_data_transferred: u64,
+    /// Earliest period `calculate_node_reward` may run for next
+    pub next_calculation_period: u64,
+    /// Rewards computed by `calculate_node_reward` and not yet claimed;
+    /// `claim_with_proof` cannot credit more than this
+    pub calculated_reward: u64,
+    /// Credited rewards still held in vesting schedules
+    pub locked_rewards: u64,
+    /// Vesting schedules created so far, seeds the next one
+    pub vesting_count: u32,
+    /// Whether claims are paid out through a `RewardSplit`
+    pub split_configured: bool,
 }
 
 impl NodeRewardAccount {
     pub const LEN: usize = 8 + // discriminator
         32 + // node_account
+        8 + // registration_nonce
         32 + // owner
         8 + // total_earned
         8 + // total_claimed
         8 + // last_calculated_period
-        8; // pending_rewards
+        8 + // pending_rewards
+        8 + // last_data_transferred
+        8 + // next_calculation_period
+        8 + // calculated_reward
+        8 + // locked_rewards
+        4 + // vesting_count
+        1; // split_configured
+}
+
+/// Beneficiaries sharing a node's claims, at
+/// `[b"reward_split", node_reward_account]`; see `split`
+#[account]
+#[derive(Default)]
+pub struct RewardSplit {
+    /// Reward account whose claims are split
+    pub node_reward_account: Pubkey,
+    /// Wallets paid on each claim, the first receives rounding dust
+    pub beneficiaries: Vec<Beneficiary>,
+    /// The split cannot be changed before this time
+    pub locked_until: i64,
+}
+
+impl RewardSplit {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // node_reward_account
+        4 + MAX_BENEFICIARIES * Beneficiary::LEN + // beneficiaries
+        8; // locked_until
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Beneficiary {
+    /// Owner of the token account the share is paid to
+    pub wallet: Pubkey,
+    /// Share of each claim (basis points)
+    pub share_bps: u16,
+}
+
+impl Beneficiary {
+    pub const LEN: usize = 32 + 2;
+}
+
+/// Locked part of one credited reward, at
+/// `[b"vesting", node_reward_account, index]`; see `vesting`
+#[account]
+#[derive(Default)]
+pub struct VestingSchedule {
+    /// Reward account the schedule releases to
+    pub node_reward_account: Pubkey,
+    /// Amount locked
+    pub total_amount: u64,
+    /// Amount released by `claim_vested` so far
+    pub released_amount: u64,
+    /// When vesting starts
+    pub start: i64,
+    /// Nothing unlocks before this time
+    pub cliff: i64,
+    /// When the full amount has vested
+    pub end: i64,
+}
+
+impl VestingSchedule {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // node_reward_account
+        8 + // total_amount
+        8 + // released_amount
+        8 + // start
+        8 + // cliff
+        8; // end
 }
 
 #[account]
+#[derive(Default)]
 pub struct RewardDistribution {
     /// Period this distribution is for
     pub period: u64,
@@ -80,10 +229,14 @@ pub struct RewardDistribution {
     pub total_nodes: u32,
     /// Total amount distributed
     pub total_amount: u64,
-    /// Distribution timestamp
+    /// When the distribution was opened
+    pub opened_at: i64,
+    /// Distribution timestamp, set on finalization
     pub distributed_at: i64,
-    /// Merkle root for verification (if using merkle distribution)
-    pub merkle_root: [u8; 32],
+    /// Number of `RewardBatch`es appended
+    pub batch_count: u16,
+    /// Whether the distribution is closed to new batches and open to claims
+    pub finalized: bool,
 }
 
 impl RewardDistribution {
@@ -91,6 +244,68 @@ impl RewardDistribution {
         8 + // period
         4 + // total_nodes
         8 + // total_amount
+        8 + // opened_at
         8 + // distributed_at
-        32; // merkle_root
+        2 + // batch_count
+        1; // finalized
+}
+
+/// One transaction's share of a distribution, at
+/// `[b"reward_batch", period, batch]`. Claims are verified against its root
+/// and recorded in its bitmap, one bit per leaf.
+#[account]
+#[derive(Default)]
+pub struct RewardBatch {
+    /// Period of the distribution this batch belongs to
+    pub period: u64,
+    /// Position of this batch in the distribution
+    pub batch: u16,
+    /// Root of the `thepublic_reward_merkle` tree of per-node amounts
+    pub merkle_root: [u8; 32],
+    /// Leaves in the tree
+    pub total_nodes: u32,
+    /// Sum of the tree's amounts
+    pub total_amount: u64,
+    /// Amount claimed against the root so far
+    pub claimed_amount: u64,
+    pub bits: Vec<u8>,
 }
+
+impl RewardBatch {
+    /// Space for a batch of `total_nodes` leaves
+    pub fn space(total_nodes: u32) -> usize {
+        8 + // discriminator
+        8 + // period
+        2 + // batch
+        32 + // merkle_root
+        4 + // total_nodes
+        8 + // total_amount
+        8 + // claimed_amount
+        4 + Self::bytes_for(total_nodes) // bits
+    }
+
+    /// Bytes of `bits` covering `total_nodes` leaves
+    #[allow(clippy::manual_div_ceil)] // `div_ceil` is newer than the SBF toolchain
+    pub fn bytes_for(total_nodes: u32) -> usize {
+        (total_nodes as usize + 7) / 8
+    }
+
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.bits[index as usize / 8] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u32) {
+        self.bits[index as usize / 8] |= 1 << (index % 8);
+    }
+}
+
+/// Largest batch whose claim bitmap fits in one account creation
+pub const MAX_BATCH_NODES: u32 = 80_000;
+
+/// Maximum beneficiaries in a `RewardSplit`
+pub const MAX_BENEFICIARIES: usize = 8;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Bytes `RewardParams::data_reward_rate` is quoted per
+pub const BYTES_PER_GB: u64 = 1_000_000_000;
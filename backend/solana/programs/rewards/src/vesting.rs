@@ -0,0 +1,244 @@
+//! Linear vesting with an optional cliff for the locked part of credited rewards.
+//!
+//! When a claim is credited, `RewardParams::vesting_bps` of it is locked in a
+//! `VestingSchedule` that unlocks linearly from the credit time over
+//! `vesting_duration` seconds. Nothing unlocks before `vesting_cliff` seconds
+//! have passed; at the cliff the linear amount accrued so far unlocks at once.
+
+use anchor_lang::prelude::*;
+use crate::errors::*;
+use crate::state::*;
+
+/// Splits a credited `amount` into its immediately claimable and locked parts
+pub fn split(amount: u64, vesting_bps: u16) -> (u64, u64) {
+    let locked = (amount as u128 * vesting_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+    (amount - locked, locked)
+}
+
+/// Portion of `total` unlocked at `now` for a schedule running from `start`
+/// to `end` that releases nothing before `cliff`
+pub fn vested_amount(total: u64, start: i64, cliff: i64, end: i64, now: i64) -> u64 {
+    if now < start || now < cliff {
+        return 0;
+    }
+    if now >= end {
+        return total;
+    }
+
+    let elapsed = (now - start) as u128;
+    let duration = (end - start) as u128;
+    (total as u128 * elapsed / duration) as u64
+}
+
+/// Credits a claimed `amount` to a node's reward account, locking the vesting
+/// share in `vesting_schedule`. The schedule must be passed exactly when
+/// something is locked: an unused one would still be created at the next
+/// `vesting_count` seed and block every later locked claim.
+pub fn credit(
+    node_reward_account: &mut NodeRewardAccount,
+    node_reward_key: Pubkey,
+    vesting_schedule: Option<&mut VestingSchedule>,
+    params: &RewardParams,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    let (immediate, locked) = split(amount, params.vesting_bps);
+
+    if locked > 0 {
+        let vesting_schedule = vesting_schedule.ok_or(RewardsError::VestingScheduleRequired)?;
+
+        vesting_schedule.node_reward_account = node_reward_key;
+        vesting_schedule.total_amount = locked;
+        vesting_schedule.released_amount = 0;
+        vesting_schedule.start = now;
+        vesting_schedule.cliff = now
+            .checked_add(params.vesting_cliff)
+            .ok_or(RewardsError::MathOverflow)?;
+        vesting_schedule.end = now
+            .checked_add(params.vesting_duration)
+            .ok_or(RewardsError::MathOverflow)?;
+
+        node_reward_account.locked_rewards = node_reward_account.locked_rewards
+            .checked_add(locked)
+            .ok_or(RewardsError::MathOverflow)?;
+        node_reward_account.vesting_count = node_reward_account.vesting_count
+            .checked_add(1)
+            .ok_or(RewardsError::MathOverflow)?;
+    } else {
+        require!(vesting_schedule.is_none(), RewardsError::UnexpectedVestingSchedule);
+    }
+
+    node_reward_account.pending_rewards = node_reward_account.pending_rewards
+        .checked_add(immediate)
+        .ok_or(RewardsError::MathOverflow)?;
+    node_reward_account.total_earned = node_reward_account.total_earned
+        .checked_add(amount)
+        .ok_or(RewardsError::MathOverflow)?;
+
+    Ok(())
+}
+
+impl VestingSchedule {
+    pub fn vested(&self, now: i64) -> u64 {
+        vested_amount(self.total_amount, self.start, self.cliff, self.end, now)
+    }
+
+    /// Unlocked amount not yet released
+    pub fn releasable(&self, now: i64) -> u64 {
+        self.vested(now).saturating_sub(self.released_amount)
+    }
+
+    pub fn is_fully_released(&self) -> bool {
+        self.released_amount >= self.total_amount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+    const START: i64 = 1_700_000_000;
+
+    fn schedule(total_amount: u64, cliff_days: i64, duration_days: i64) -> VestingSchedule {
+        VestingSchedule {
+            node_reward_account: Pubkey::new_unique(),
+            total_amount,
+            released_amount: 0,
+            start: START,
+            cliff: START + cliff_days * DAY,
+            end: START + duration_days * DAY,
+        }
+    }
+
+    #[test]
+    fn split_locks_vesting_share() {
+        assert_eq!(split(1_000, 0), (1_000, 0));
+        assert_eq!(split(1_000, 2_500), (750, 250));
+        assert_eq!(split(1_000, 10_000), (0, 1_000));
+        // Rounding favours the immediately claimable part
+        assert_eq!(split(3, 5_000), (2, 1));
+        assert_eq!(split(u64::MAX, 10_000), (0, u64::MAX));
+    }
+
+    #[test]
+    fn vests_linearly_without_cliff() {
+        let schedule = schedule(1_000, 0, 100);
+
+        assert_eq!(schedule.vested(START - 1), 0);
+        assert_eq!(schedule.vested(START), 0);
+        assert_eq!(schedule.vested(START + DAY), 10);
+        assert_eq!(schedule.vested(START + 25 * DAY), 250);
+        assert_eq!(schedule.vested(START + 50 * DAY + DAY / 2), 505);
+        assert_eq!(schedule.vested(START + 99 * DAY), 990);
+        assert_eq!(schedule.vested(START + 100 * DAY), 1_000);
+        assert_eq!(schedule.vested(START + 1_000 * DAY), 1_000);
+    }
+
+    #[test]
+    fn nothing_unlocks_before_cliff() {
+        let schedule = schedule(1_200, 30, 120);
+
+        assert_eq!(schedule.vested(START + 29 * DAY), 0);
+        assert_eq!(schedule.vested(START + 30 * DAY - 1), 0);
+        // Accrued amount unlocks at the cliff
+        assert_eq!(schedule.vested(START + 30 * DAY), 300);
+        assert_eq!(schedule.vested(START + 60 * DAY), 600);
+        assert_eq!(schedule.vested(START + 120 * DAY), 1_200);
+    }
+
+    #[test]
+    fn zero_duration_vests_immediately() {
+        let schedule = schedule(500, 0, 0);
+
+        assert_eq!(schedule.vested(START - 1), 0);
+        assert_eq!(schedule.vested(START), 500);
+    }
+
+    #[test]
+    fn releases_track_claims_through_schedule() {
+        let mut schedule = schedule(1_000, 10, 40);
+        let mut released = Vec::new();
+
+        for day in (0..=50).step_by(5) {
+            let amount = schedule.releasable(START + day * DAY);
+            schedule.released_amount += amount;
+            released.push(amount);
+        }
+
+        // Cliff at day 10, then 125 per five days until day 40
+        assert_eq!(released, [0, 0, 250, 125, 125, 125, 125, 125, 125, 0, 0]);
+        assert_eq!(schedule.released_amount, 1_000);
+        assert!(schedule.is_fully_released());
+        assert_eq!(schedule.releasable(START + 100 * DAY), 0);
+    }
+
+    fn params(vesting_bps: u16) -> RewardParams {
+        RewardParams {
+            base_reward: 0,
+            uptime_multiplier: 0,
+            data_reward_rate: 0,
+            user_reward_rate: 0,
+            min_uptime: 0,
+            period_duration: DAY as u64,
+            vesting_bps,
+            vesting_duration: 90 * DAY,
+            vesting_cliff: 30 * DAY,
+        }
+    }
+
+    fn node_reward_account() -> NodeRewardAccount {
+        NodeRewardAccount {
+            node_account: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            ..NodeRewardAccount::default()
+        }
+    }
+
+    #[test]
+    fn credit_without_vesting_share_rejects_schedule() {
+        let key = Pubkey::new_unique();
+        let mut account = node_reward_account();
+        let mut unused = schedule(0, 0, 0);
+
+        assert_eq!(
+            credit(&mut account, key, Some(&mut unused), &params(0), 1_000, START).err(),
+            Some(error!(RewardsError::UnexpectedVestingSchedule))
+        );
+        // A share that rounds to nothing locked is treated the same way
+        assert_eq!(
+            credit(&mut account, key, Some(&mut unused), &params(1), 1, START).err(),
+            Some(error!(RewardsError::UnexpectedVestingSchedule))
+        );
+
+        credit(&mut account, key, None, &params(0), 1_000, START).unwrap();
+        assert_eq!(account.pending_rewards, 1_000);
+        assert_eq!(account.total_earned, 1_000);
+        assert_eq!(account.locked_rewards, 0);
+        assert_eq!(account.vesting_count, 0);
+    }
+
+    #[test]
+    fn credit_locks_vesting_share() {
+        let key = Pubkey::new_unique();
+        let mut account = node_reward_account();
+
+        assert_eq!(
+            credit(&mut account, key, None, &params(2_500), 1_000, START).err(),
+            Some(error!(RewardsError::VestingScheduleRequired))
+        );
+
+        let mut vesting_schedule = schedule(0, 0, 0);
+        credit(&mut account, key, Some(&mut vesting_schedule), &params(2_500), 1_000, START).unwrap();
+
+        assert_eq!(account.pending_rewards, 750);
+        assert_eq!(account.locked_rewards, 250);
+        assert_eq!(account.total_earned, 1_000);
+        assert_eq!(account.vesting_count, 1);
+        assert_eq!(vesting_schedule.node_reward_account, key);
+        assert_eq!(vesting_schedule.total_amount, 250);
+        assert_eq!(vesting_schedule.start, START);
+        assert_eq!(vesting_schedule.cliff, START + 30 * DAY);
+        assert_eq!(vesting_schedule.end, START + 90 * DAY);
+    }
+}